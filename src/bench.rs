@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hint::black_box;
+use std::mem::MaybeUninit;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::libc;
+use nix::sched::{sched_setaffinity, CpuSet};
+use nix::sys::resource::{getrusage, UsageWho};
+use nix::unistd::Pid;
+
+use crate::error::Result;
+use crate::util::{
+    format_duration, format_size,
+    hash::{siphash_enabled, use_siphash},
+    preload_day, take_opt, take_switch,
+};
+use crate::{find_solution, Solution, Solutions};
+
+/// Pins the calling thread to a single CPU.
+///
+/// Threads spawned afterwards (e.g. the rayon pool) inherit the mask, so
+/// parallel solutions end up sharing the same core.
+pub fn pin_cpu(cpu: usize) -> Result<()> {
+    let mut set = CpuSet::new();
+    set.set(cpu)?;
+    sched_setaffinity(Pid::from_raw(0), &set)?;
+    Ok(())
+}
+
+/// Sets the nice value of the whole process. Negative values need
+/// `CAP_SYS_NICE`.
+pub fn set_nice(nice: i32) -> Result<()> {
+    let res = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    Errno::result(res)?;
+    Ok(())
+}
+
+/// Voluntary and involuntary context switches of the process so far.
+fn context_switches() -> Result<(i64, i64)> {
+    let usage = getrusage(UsageWho::RUSAGE_SELF)?;
+    Ok((
+        usage.voluntary_context_switches() as i64,
+        usage.involuntary_context_switches() as i64,
+    ))
+}
+
+/// Resource usage of a single solution run.
+pub struct ResourceUsage {
+    pub max_rss_kb: i64,
+    pub user: Duration,
+    pub system: Duration,
+    pub minor_faults: i64,
+    pub major_faults: i64,
+}
+
+fn timeval_to_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+}
+
+impl From<&libc::rusage> for ResourceUsage {
+    fn from(r: &libc::rusage) -> Self {
+        Self {
+            max_rss_kb: r.ru_maxrss,
+            user: timeval_to_duration(r.ru_utime),
+            system: timeval_to_duration(r.ru_stime),
+            minor_faults: r.ru_minflt,
+            major_faults: r.ru_majflt,
+        }
+    }
+}
+
+impl Display for ResourceUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "peak RSS {}, user {}, sys {}, {} minor / {} major page faults",
+            format_size(self.max_rss_kb * 1024),
+            format_duration(self.user),
+            format_duration(self.system),
+            self.minor_faults,
+            self.major_faults,
+        )
+    }
+}
+
+/// Resource usage of the current process so far.
+pub fn self_usage() -> Result<ResourceUsage> {
+    Ok(getrusage(UsageWho::RUSAGE_SELF)?.as_ref().into())
+}
+
+/// Runs a single solution in a fresh child process, so that its peak RSS is
+/// not hidden by whatever ran before it in the bench process.
+pub fn child_usage(day_no: usize, part_no: usize, name: &str) -> Result<ResourceUsage> {
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args([&day_no.to_string(), &part_no.to_string(), "--variant", name]);
+    if siphash_enabled() {
+        cmd.arg("--siphash");
+    }
+    cmd.args(["--threads", &rayon::current_num_threads().to_string()]);
+    let child = cmd.stdout(Stdio::null()).spawn()?;
+    let mut status = 0;
+    let mut usage = MaybeUninit::<libc::rusage>::uninit();
+    let res = unsafe {
+        libc::wait4(
+            child.id() as libc::pid_t,
+            &mut status,
+            0,
+            usage.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+    if !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+        return Err(format!("{name} failed with wait status {status}").into());
+    }
+    Ok(unsafe { usage.assume_init_ref() }.into())
+}
+
+/// Average time of a solution over repeated runs that take at least
+/// `duration` in total, and the number of runs.
+fn measure(solution: &Solution, duration: Duration) -> (Duration, u32) {
+    let tic = Instant::now();
+    black_box(solution());
+    let single_sample = tic.elapsed();
+    let mut elapsed = single_sample;
+    let mut samples = 1;
+
+    let sample_chunk = 1.max((duration.as_nanos() / single_sample.as_nanos() / 10) as u32);
+    while elapsed < duration {
+        let tic = Instant::now();
+        for _ in 0..sample_chunk {
+            black_box(solution());
+        }
+        elapsed += tic.elapsed();
+        samples += sample_chunk;
+    }
+    (elapsed / samples, samples)
+}
+
+/// How many times faster the `FastMap` hasher is than SipHash.
+fn speedup(fast: Duration, sip: Duration) -> String {
+    format!("{:.2}x", sip.as_secs_f64() / fast.as_secs_f64())
+}
+
+/// Keeps the best time of a part over its variants.
+fn keep_best(results: &mut HashMap<(usize, usize), Duration>, key: (usize, usize), avg: Duration) {
+    results
+        .entry(key)
+        .and_modify(|x| {
+            if *x > avg {
+                *x = avg;
+            }
+        })
+        .or_insert(avg);
+}
+
+pub fn run(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    if let Some(cpu) = take_opt(&mut args, "--pin-cpu")? {
+        pin_cpu(cpu.parse()?)?;
+    }
+    if let Some(nice) = take_opt(&mut args, "--nice")? {
+        set_nice(nice.parse()?)?;
+    }
+    let rusage = !take_switch(&mut args, "--no-rusage");
+    let compare_hashers = take_switch(&mut args, "--compare-hashers");
+
+    let mut results = HashMap::new();
+    let mut sip_results = HashMap::new();
+    let which = args.first().cloned().unwrap_or("all".to_string());
+    let which = which.split(",").collect::<Vec<_>>();
+    let which: Vec<_> = if which[0] == "all" {
+        (0..solutions.len()).collect()
+    } else {
+        which
+            .iter()
+            .filter_map(|x| x.parse::<usize>().ok().map(|x| x - 1))
+            .collect()
+    };
+    let mut total = Duration::ZERO;
+    let duration_per_test = Duration::from_millis(5000);
+    let siphash = siphash_enabled();
+    for i in which.iter() {
+        let day = &solutions[*i];
+        let day_no = i + 1;
+        println!("Day {}", day_no);
+        for (part_no, part) in day.iter().enumerate() {
+            let part_no = part_no + 1;
+            for (name, solution) in part {
+                let (vol_before, invol_before) = context_switches()?;
+                if compare_hashers {
+                    use_siphash(false);
+                }
+                let (avg, samples) = measure(solution, duration_per_test);
+                let (vol_after, invol_after) = context_switches()?;
+                keep_best(&mut results, (day_no, part_no), avg);
+                total += avg;
+                println!(
+                    "{name} computed in {} ({samples} samples, {} voluntary / {} involuntary context switches)",
+                    format_duration(avg),
+                    vol_after - vol_before,
+                    invol_after - invol_before,
+                );
+                if compare_hashers {
+                    use_siphash(true);
+                    let (sip_avg, samples) = measure(solution, duration_per_test);
+                    use_siphash(siphash);
+                    keep_best(&mut sip_results, (day_no, part_no), sip_avg);
+                    println!(
+                        "    with SipHash in {} ({samples} samples, {} speedup of the fast hasher)",
+                        format_duration(sip_avg),
+                        speedup(avg, sip_avg),
+                    );
+                }
+                if rusage {
+                    println!("    {}", child_usage(day_no, part_no, name)?);
+                }
+            }
+        }
+        println!("");
+    }
+
+    println!("Total: {}", format_duration(total));
+    println!("");
+    println!("");
+    if compare_hashers {
+        println!("Day     Part 1     SipHash  Speedup      Part 2     SipHash  Speedup");
+    } else {
+        println!("Day     Part 1      Part 2");
+    }
+
+    let mut times = results.values().copied().collect::<Vec<_>>();
+    times.sort();
+    let lower_quartile = times[times.len() / 4];
+    let upper_quartile = times[times.len() * 3 / 4];
+    let mut total_best = Duration::ZERO;
+    let mut total_sip = Duration::ZERO;
+    for i in which.iter() {
+        let day_no = i + 1;
+        print!("{: >2}", day_no);
+        for part_no in [1, 2] {
+            match results.get(&(day_no, part_no)) {
+                Some(x) => {
+                    total_best += *x;
+                    print!(
+                        "  \x1b[{}m{: >10}\x1b[0m",
+                        if *x <= lower_quartile {
+                            32
+                        } else if *x >= upper_quartile {
+                            91
+                        } else {
+                            93
+                        },
+                        format_duration(*x)
+                    );
+                    if let Some(sip) = sip_results.get(&(day_no, part_no)) {
+                        total_sip += *sip;
+                        print!(
+                            "  {: >10}  {: >7}",
+                            format_duration(*sip),
+                            speedup(*x, *sip)
+                        );
+                    }
+                }
+                None => {
+                    print!("         n/a");
+                    if compare_hashers {
+                        print!("         n/a      n/a");
+                    }
+                }
+            }
+        }
+        println!("");
+    }
+    println!("");
+    println!("Total: {}", format_duration(total_best));
+    if compare_hashers {
+        println!(
+            "Total with SipHash: {} ({})",
+            format_duration(total_sip),
+            speedup(total_best, total_sip)
+        );
+    }
+    Ok(())
+}
+
+/// Runs one solution in a tight loop for a fixed number of iterations or a
+/// fixed duration, without the adaptive chunking of [`run`], so that an
+/// external profiler can attach to a steady-state process.
+pub fn profile(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let variant = take_opt(&mut args, "--variant")?;
+    let iterations = take_opt(&mut args, "--iterations")?
+        .map(|x| x.parse::<u64>())
+        .transpose()?;
+    let duration = take_opt(&mut args, "--seconds")?
+        .map(|x| x.parse::<f64>().map(Duration::from_secs_f64))
+        .transpose()?;
+    if iterations.is_some() == duration.is_some() {
+        return Err("expected exactly one of --iterations N or --seconds S".into());
+    }
+    let day_no: usize = args.first().ok_or("missing day")?.parse()?;
+    let part_no: usize = args.get(1).map_or("1", |x| x.as_str()).parse()?;
+    let (name, solution) = find_solution(solutions, day_no, part_no, variant.as_deref())?;
+
+    preload_day(day_no)?;
+    println!("PID {}", std::process::id());
+    println!("Profiling {name}");
+
+    let mut done = 0;
+    let tic = Instant::now();
+    loop {
+        if iterations.is_some_and(|n| done >= n) || duration.is_some_and(|d| tic.elapsed() >= d) {
+            break;
+        }
+        black_box(solution());
+        done += 1;
+    }
+    let elapsed = tic.elapsed();
+    println!(
+        "{done} iterations in {} ({} per iteration)",
+        format_duration(elapsed),
+        format_duration(Duration::from_nanos(
+            (elapsed.as_nanos() / done.max(1) as u128) as u64
+        ))
+    );
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::util::{parse_duration, take_opt};
+use crate::Solutions;
+
+/// Outcome of running one variant in a child process.
+#[derive(Clone)]
+pub enum Outcome {
+    /// The `Debug` output of the result and the time the child reported.
+    Answer(String, Option<Duration>),
+    /// The location and message of the panic.
+    Panicked(String, String),
+    Failed,
+    Timeout,
+}
+
+/// Runs `variant` in a child process of `exe`, optionally with `input` in
+/// place of the day's puzzle input.
+pub fn run_variant(
+    exe: &Path,
+    day_no: usize,
+    part_no: usize,
+    variant: &str,
+    input: Option<&Path>,
+    timeout: Duration,
+) -> Result<Outcome> {
+    let mut command = Command::new(exe);
+    command.args([
+        &day_no.to_string(),
+        &part_no.to_string(),
+        "--variant",
+        variant,
+    ]);
+    if let Some(input) = input {
+        command.arg("--input").arg(input);
+    }
+    let mut child = command
+        .env("RUST_BACKTRACE", "0")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // read on the side so that a chatty child cannot fill the pipe and stall
+    let mut stderr = child.stderr.take().unwrap();
+    let stderr = std::thread::spawn(move || {
+        let mut s = String::new();
+        let _ = stderr.read_to_string(&mut s);
+        s
+    });
+    let tic = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if tic.elapsed() > timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(Outcome::Timeout);
+        }
+        sleep(Duration::from_millis(5));
+    };
+    let mut stdout = String::new();
+    child.stdout.take().unwrap().read_to_string(&mut stdout)?;
+    let answer = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("Result: "))
+        .map(|x| x.to_string());
+    let elapsed = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("Computed in "))
+        .and_then(parse_duration);
+    let stderr = stderr.join().unwrap_or_default();
+    Ok(match (answer, panic_site(&stderr)) {
+        (Some(answer), _) if status.success() => Outcome::Answer(answer, elapsed),
+        (_, Some((location, message))) => Outcome::Panicked(location, message),
+        _ => Outcome::Failed,
+    })
+}
+
+/// Location and message of the first panic reported in `stderr`.
+fn panic_site(stderr: &str) -> Option<(String, String)> {
+    let mut lines = stderr.lines();
+    let location = lines.find_map(|l| Some(l.split_once(" panicked at ")?.1))?;
+    let message = lines.next().unwrap_or_default();
+    Some((
+        location.trim_end_matches(':').to_string(),
+        message.to_string(),
+    ))
+}
+
+/// Reads `<input>.answers`, one `<part> <answer>` per line.
+fn expected_answers(input: &Path) -> Result<BTreeMap<usize, String>> {
+    let mut path = input.as_os_str().to_owned();
+    path.push(".answers");
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let mut answers = BTreeMap::new();
+    for l in fs::read_to_string(path)?.lines() {
+        if let Some((part, answer)) = l.trim().split_once(' ') {
+            answers.insert(part.parse()?, answer.trim().to_string());
+        }
+    }
+    Ok(answers)
+}
+
+/// Compares the `Debug` output of a result (`Ok(42)`, `Ok("CMZ")`) with a
+/// plain expected answer.
+fn answer_matches(answer: &str, expected: &str) -> bool {
+    let answer = answer
+        .strip_prefix("Ok(")
+        .and_then(|x| x.strip_suffix(')'))
+        .unwrap_or(answer);
+    answer == expected || answer.trim_matches('"') == expected
+}
+
+fn corpus_inputs(day_no: usize) -> Result<Vec<PathBuf>> {
+    let dir = PathBuf::from(format!("inputs/corpus/day{:02}", day_no));
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_none_or(|x| x != "answers") {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Runs every variant of the selected days against every file in
+/// `inputs/corpus/dayNN/` and reports which inputs break which variants.
+pub fn run(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let timeout = take_opt(&mut args, "--timeout")?
+        .map(|x| x.parse::<f64>().map(Duration::from_secs_f64))
+        .transpose()?
+        .unwrap_or(Duration::from_secs(10));
+    let which = args.first().cloned().unwrap_or("all".to_string());
+    let which: Vec<usize> = if which == "all" {
+        (1..=solutions.len()).collect()
+    } else {
+        which
+            .split(',')
+            .filter_map(|x| x.parse::<usize>().ok())
+            .collect()
+    };
+
+    let exe = std::env::current_exe()?;
+    let mut broken = Vec::new();
+    for day_no in which {
+        let inputs = corpus_inputs(day_no)?;
+        if inputs.is_empty() {
+            continue;
+        }
+        println!("Day {}", day_no);
+        for input in inputs.iter() {
+            println!("  {}", input.display());
+            let expected = expected_answers(input)?;
+            for (part_no, part) in solutions[day_no - 1].iter().enumerate() {
+                let part_no = part_no + 1;
+                for name in part.keys() {
+                    let outcome = run_variant(&exe, day_no, part_no, name, Some(input), timeout)?;
+                    let status = match (&outcome, expected.get(&part_no)) {
+                        (Outcome::Answer(a, _), Some(e)) if answer_matches(a, e) => {
+                            format!("\x1b[32mok\x1b[0m {a}")
+                        }
+                        (Outcome::Answer(a, _), Some(e)) => {
+                            broken.push((*name, input.clone(), format!("expected {e}, got {a}")));
+                            format!("\x1b[91mwrong\x1b[0m {a} (expected {e})")
+                        }
+                        (Outcome::Answer(a, _), None) => a.clone(),
+                        (Outcome::Panicked(at, msg), _) => {
+                            broken.push((*name, input.clone(), format!("panicked at {at}")));
+                            format!("\x1b[91mpanicked\x1b[0m at {at}: {msg}")
+                        }
+                        (Outcome::Failed, _) => {
+                            broken.push((*name, input.clone(), "failed".to_string()));
+                            "\x1b[91mfailed\x1b[0m".to_string()
+                        }
+                        (Outcome::Timeout, _) => {
+                            broken.push((*name, input.clone(), "timed out".to_string()));
+                            "\x1b[93mtimeout\x1b[0m".to_string()
+                        }
+                    };
+                    println!("    {name}: {status}");
+                }
+            }
+        }
+        println!();
+    }
+
+    if broken.is_empty() {
+        println!("All variants handled all corpus inputs");
+    } else {
+        println!("Broken:");
+        for (name, input, reason) in broken {
+            println!("  {name} on {}: {reason}", input.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answer_matches() {
+        assert!(answer_matches("Ok(42)", "42"));
+        assert!(answer_matches("Ok(\"CMZ\")", "CMZ"));
+        assert!(answer_matches("Ok((2, 4))", "(2, 4)"));
+        assert!(!answer_matches("Ok(42)", "4"));
+    }
+
+    #[test]
+    fn test_panic_site() {
+        let stderr = "\nthread 'main' (23193) panicked at src/day18.rs:48:9:\n\
+                      index out of bounds: the len is 22 but the index is 30\n\
+                      note: run with `RUST_BACKTRACE=1`\n";
+        assert_eq!(
+            panic_site(stderr),
+            Some((
+                "src/day18.rs:48:9".to_string(),
+                "index out of bounds: the len is 22 but the index is 30".to_string()
+            ))
+        );
+        assert_eq!(panic_site("Error: bad input\n"), None);
+    }
+}
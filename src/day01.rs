@@ -1,25 +1,65 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
 use crate::error::Result;
-use crate::util::read_string;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::util::{
+    input::{blocks, lines},
+    read_bytes,
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Calorie Counting",
+    tags: &[Tag::Parsing],
+    status: Status::Combined,
+};
 
+/// Keeps the three largest sums sorted in `top_s[1..]`, `top_s[0]` is scratch.
 fn insert(top_s: &mut [i64], s: i64) {
     top_s[0] = s;
     top_s.sort();
 }
 
-pub fn solve_a() -> Result<()> {
-    let mut s = 0;
+/// The calories carried by each elf.
+pub fn parse(input: &[u8]) -> Result<Vec<i64>> {
+    let mut elves = Vec::new();
+    for block in blocks(input) {
+        let mut s = 0;
+        for l in lines(block) {
+            s += std::str::from_utf8(l)?.parse::<i64>()?;
+        }
+        elves.push(s);
+    }
+    Ok(elves)
+}
+
+pub fn solve_a() -> Result<(i64, i64)> {
     let mut top_s = [0i64, 0i64, 0i64, 0i64];
-    for l in read_string("inputs/day01a")?.lines() {
-        if l == "" {
-            insert(&mut top_s, s);
-            s = 0;
-        } else {
-            s += l.parse::<i64>()?;
+    for s in parse(&read_bytes("inputs/day01a")?)? {
+        insert(&mut top_s, s);
+    }
+    Ok((top_s[3], top_s[1..].iter().sum()))
+}
+
+/// Generates `n` elves carrying one to five items each.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut s = String::new();
+    for _ in 0..n {
+        for _ in 0..rng.gen_range(1..=5) {
+            s += &format!("{}\n", rng.gen_range(1000..60000));
         }
+        s += "\n";
+    }
+    s.into_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        assert_eq!(solve_a().unwrap(), (69912, 208180));
     }
-    insert(&mut top_s, s);
-    // println!("{}", top_s[0]);
-    // println!("{}", top_s[1..].iter().sum::<i64>());
-    // println!("{:?}", top_s.as_slice());
-    Ok(())
 }
@@ -1,10 +1,18 @@
 use crate::error::Result;
-use crate::util::read_string;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::util::read_bytes;
 use rand::distributions::Uniform;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::arch::x86_64::{__m256i, _mm256_shuffle_epi8};
 use std::fs;
-use std::simd::{u8x32, SimdUint};
+use std::simd::{num::SimdUint, u8x32};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Rock Paper Scissors",
+    tags: &[Tag::Bitwise],
+    status: Status::Solved,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RPS {
@@ -71,13 +79,23 @@ fn dominated_by(x: RPS) -> RPS {
     }
 }
 
-pub fn solve_b() -> Result<i64> {
-    let mut score = 0;
-    for l in read_string("inputs/day02a")?.lines() {
+/// The two columns of every round.
+pub fn parse(input: &[u8]) -> Result<Vec<(char, char)>> {
+    let mut rounds = Vec::new();
+    for l in std::str::from_utf8(input)?.lines() {
         let mut s = l.chars();
-        let other = convert_other(s.next().unwrap());
+        let first = s.next().unwrap();
         s.next();
-        let desired_outcome = convert_outcome(s.next().unwrap());
+        rounds.push((first, s.next().unwrap()));
+    }
+    Ok(rounds)
+}
+
+pub fn solve_b() -> Result<i64> {
+    let mut score = 0;
+    for (other, outcome) in parse(&read_bytes("inputs/day02a")?)? {
+        let other = convert_other(other);
+        let desired_outcome = convert_outcome(outcome);
         let mine = match desired_outcome {
             Outcome::Win => dominated_by(other),
             Outcome::Lose => dominates(other),
@@ -138,11 +156,9 @@ pub fn gen_lut() {
 
 pub fn solve_a() -> Result<i64> {
     let mut score = 0;
-    for l in read_string("inputs/day02a")?.lines() {
-        let mut s = l.chars();
-        let other = convert_other(s.next().unwrap());
-        s.next();
-        let mine = convert_mine(s.next().unwrap());
+    for (other, mine) in parse(&read_bytes("inputs/day02a")?)? {
+        let other = convert_other(other);
+        let mine = convert_mine(mine);
 
         let outcome = if mine == other {
             Outcome::Draw
@@ -162,19 +178,24 @@ pub fn solve_a() -> Result<i64> {
     Ok(score)
 }
 
-#[allow(unused)]
-pub fn gen_input_day2() -> Result<()> {
+/// Generates `n` rounds of random input.
+pub fn gen_input(n: usize) -> Vec<u8> {
     let mut s = Vec::<u8>::new();
 
-    let mut rng = rand::thread_rng();
-    for _ in 0..100_000_000 {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    for _ in 0..n {
         let idx = rng.sample(Uniform::new(0, 3));
         s.push(['A' as u8, 'B' as u8, 'C' as u8][idx]);
         s.push(' ' as u8);
         s.push(['X' as u8, 'Y' as u8, 'Z' as u8][idx]);
         s.push('\n' as u8);
     }
-    fs::write("day02g", s)?;
+    s
+}
+
+#[allow(unused)]
+pub fn gen_input_day2() -> Result<()> {
+    fs::write("day02g", gen_input(100_000_000))?;
     Ok(())
 }
 
@@ -186,7 +207,7 @@ pub fn gen_input_day2() -> Result<()> {
 
 pub fn solve_b_opt() -> Result<i64> {
     let mut score = 0i64;
-    let v = fs::read("inputs/day02a")?;
+    let v = read_bytes("inputs/day02a")?;
     let lut = [
         2, 3, 7, 0, 4, 8, 1, 5, 6, 0, 0, 0, 0, 0, 0, 0, 2, 3, 7, 0, 4, 8, 1, 5, 6, 0, 0, 0, 0, 0,
         0, 0,
@@ -197,7 +218,7 @@ pub fn solve_b_opt() -> Result<i64> {
         0x00, 0xff, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0xff, 0x00,
         0x00, 0x00,
     ]);
-    let m_d2 = m_d1.rotate_lanes_right::<2>();
+    let m_d2 = m_d1.rotate_elements_right::<2>();
     let mut chunks = v.chunks_exact(32 * 4);
     for l in &mut chunks {
         let l1 = u8x32::from_slice(&l[32 * 0..32 * 1]);
@@ -216,13 +237,13 @@ pub fn solve_b_opt() -> Result<i64> {
         let l4_d2 = l4 & m_d2;
 
         let d1s = l1_d1
-            | l2_d1.rotate_lanes_right::<1>()
-            | l3_d1.rotate_lanes_right::<2>()
-            | l4_d1.rotate_lanes_right::<3>();
-        let d2s = l1_d2.rotate_lanes_left::<2>()
-            | l2_d2.rotate_lanes_left::<1>()
+            | l2_d1.rotate_elements_right::<1>()
+            | l3_d1.rotate_elements_right::<2>()
+            | l4_d1.rotate_elements_right::<3>();
+        let d2s = l1_d2.rotate_elements_left::<2>()
+            | l2_d2.rotate_elements_left::<1>()
             | l3_d2
-            | l4_d2.rotate_lanes_right::<1>();
+            | l4_d2.rotate_elements_right::<1>();
         let vals = (u8x32::from(d1s) - u8x32::splat('A' as u8)) * u8x32::splat(3)
             + (u8x32::from(d2s) - u8x32::splat('X' as u8));
         let vals = __m256i::from(vals);
@@ -240,7 +261,7 @@ pub fn solve_b_opt() -> Result<i64> {
 
 pub fn solve_b_opt_2() -> Result<i64> {
     let mut score = 0i64;
-    let v = fs::read("inputs/day02a")?;
+    let v = read_bytes("inputs/day02a")?;
     let lut = [2, 3, 7, 0, 4, 8, 1, 5, 6];
     for l in 0..v.len() / 4 {
         let d1 = v[l * 4] - 'A' as u8;
@@ -1,4 +1,11 @@
-use crate::{error::Result, util::read_string};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{error::Result, util::read_bytes};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Rucksack Reorganization",
+    tags: &[Tag::Bitwise],
+    status: Status::Solved,
+};
 
 fn item_set(s: &[u8]) -> u64 {
     let mut x: u64 = 0;
@@ -11,9 +18,14 @@ fn item_set(s: &[u8]) -> u64 {
     x
 }
 
+/// The rucksacks, one per line.
+pub fn parse(input: &[u8]) -> Result<Vec<&str>> {
+    Ok(std::str::from_utf8(input)?.lines().collect())
+}
+
 pub fn solve_a() -> Result<u32> {
     let mut s = 0;
-    for l in read_string("inputs/day03a")?.lines() {
+    for l in parse(&read_bytes("inputs/day03a")?)? {
         let l = l.as_bytes();
         let left = &l[..l.len() / 2];
         let right = &l[l.len() / 2..];
@@ -27,7 +39,7 @@ pub fn solve_b() -> Result<u32> {
     let mut s = 0;
     let mut running_intersection = !0u64;
     let mut group_member_count = 0;
-    for l in read_string("inputs/day03a")?.lines() {
+    for l in parse(&read_bytes("inputs/day03a")?)? {
         running_intersection &= item_set(l.as_bytes());
         group_member_count += 1;
         if group_member_count == 3 {
@@ -1,31 +1,63 @@
-use crate::{error::Result, util::read_string};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-pub fn solve_a() -> Result<(i32, i32)> {
-    let mut s1 = 0;
-    let mut s2 = 0;
-    for pair in read_string("inputs/day04a")?.lines() {
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{read_bytes, RangeExt},
+};
+use std::ops::RangeInclusive;
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Camp Cleanup",
+    tags: &[Tag::Intervals],
+    status: Status::Combined,
+};
+
+/// The section ranges of every pair of elves.
+pub fn parse(input: &[u8]) -> Result<Vec<(RangeInclusive<u64>, RangeInclusive<u64>)>> {
+    let mut pairs = Vec::new();
+    for pair in std::str::from_utf8(input)?.lines() {
         let mut i = pair.split(',');
         let a = i.next().unwrap();
         let b = i.next().unwrap();
         let mut i = a.split('-');
-        let a_lo = i.next().unwrap().parse::<u64>()?;
-        let a_hi = i.next().unwrap().parse::<u64>()?;
+        let a = i.next().unwrap().parse::<u64>()?..=i.next().unwrap().parse()?;
         let mut i = b.split('-');
-        let b_lo = i.next().unwrap().parse::<u64>()?;
-        let b_hi = i.next().unwrap().parse::<u64>()?;
+        let b = i.next().unwrap().parse::<u64>()?..=i.next().unwrap().parse()?;
+        pairs.push((a, b));
+    }
+    Ok(pairs)
+}
 
-        if (a_lo <= b_lo && b_hi <= a_hi) || (b_lo <= a_lo && a_hi <= b_hi) {
+pub fn solve_a() -> Result<(i32, i32)> {
+    let mut s1 = 0;
+    let mut s2 = 0;
+    for (a, b) in parse(&read_bytes("inputs/day04a")?)? {
+        if a.contains_range(&b) || b.contains_range(&a) {
             s1 += 1;
         }
-        if (a_lo <= b_lo && b_hi <= a_hi)
-            || (b_lo <= a_lo && a_hi <= b_hi)
-            || (a_hi >= b_lo && a_hi <= b_hi)
-            || (a_lo >= b_lo && a_lo <= b_hi)
-            || (b_hi >= a_lo && b_hi <= a_hi)
-            || (b_lo >= a_lo && b_lo <= a_hi)
-        {
+        if a.overlaps(&b) {
             s2 += 1;
         }
     }
     Ok((s1, s2))
 }
+
+/// Generates `n` random pairs of sections.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut s = String::new();
+    for _ in 0..n {
+        let a = rng.gen_range(1..99);
+        let b = rng.gen_range(1..99);
+        s += &format!(
+            "{}-{},{}-{}\n",
+            a,
+            rng.gen_range(a..=99),
+            b,
+            rng.gen_range(b..=99)
+        );
+    }
+    s.into_bytes()
+}
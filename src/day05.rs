@@ -1,4 +1,18 @@
-use crate::{error::Result, util::read_string};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{
+        input::{columns, lines, split_header},
+        ints::extract_ints,
+        read_bytes,
+    },
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Supply Stacks",
+    tags: &[Tag::Parsing, Tag::Simulation],
+    status: Status::Solved,
+};
 
 // use nom::{
 //     bytes::complete::tag,
@@ -24,102 +38,68 @@ use crate::{error::Result, util::read_string};
 //     Ok((input, (letter, number.parse().unwrap())))
 // }
 
-pub fn solve_a() -> Result<String> {
-    let mut stacks = Vec::new();
-
-    let s = read_string("inputs/day05a")?;
-    let mut lines = s.lines();
-
-    for l in &mut lines {
-        if stacks.len() == 0 {
-            for _ in 0..l.len() / 4 + 1 {
-                stacks.push(Vec::new());
+/// The stacks drawn above the numbering line, bottom crate first.
+fn parse_stacks(header: &[u8]) -> Vec<Vec<u8>> {
+    let rows = lines(header).collect::<Vec<_>>();
+    let (numbers, rows) = rows.split_last().unwrap();
+    let mut stacks = vec![Vec::new(); columns(numbers, 4).count()];
+    for row in rows.iter().rev() {
+        for (stack, cell) in stacks.iter_mut().zip(columns(row, 4)) {
+            match cell.get(1) {
+                Some(b' ') | None => {}
+                Some(&c) => stack.push(c),
             }
         }
+    }
+    stacks
+}
 
-        if l.as_bytes()[1] == '1' as u8 {
-            break;
-        }
+/// `[n, from, to]` for every line like `move 1 from 2 to 1`.
+fn parse_moves(body: &[u8]) -> Result<Vec<[usize; 3]>> {
+    lines(body)
+        .map(|l| {
+            let mut n = extract_ints(l);
+            let mut next = || n.next().ok_or("incomplete move");
+            Ok([next()?, next()?, next()?])
+        })
+        .collect()
+}
 
-        for i in 0..stacks.len() {
-            let c = l.as_bytes()[i * 4 + 1];
-            if c != ' ' as u8 {
-                stacks[i].push(c);
-            }
-        }
-    }
+fn tops(stacks: &[Vec<u8>]) -> String {
+    stacks
+        .iter()
+        .map(|x| *x.last().unwrap_or(&b' ') as char)
+        .collect()
+}
 
-    for s in stacks.iter_mut() {
-        s.reverse();
-    }
+type Stacks = Vec<Vec<u8>>;
+
+/// The stacks and the moves.
+pub fn parse(input: &[u8]) -> Result<(Stacks, Vec<[usize; 3]>)> {
+    let (header, body) = split_header(input);
+    Ok((parse_stacks(header), parse_moves(body)?))
+}
 
-    lines.next().unwrap();
-    for l in &mut lines {
-        let s = l.split(' ').collect::<Vec<_>>();
-        let n: u64 = s[1].parse()?;
-        let from: usize = s[3].parse()?;
-        let to: usize = s[5].parse()?;
+pub fn solve_a() -> Result<String> {
+    let (mut stacks, moves) = parse(&read_bytes("inputs/day05a")?)?;
 
+    for [n, from, to] in moves {
         for _ in 0..n {
             let lift = stacks[from - 1].pop().unwrap();
             stacks[to - 1].push(lift);
         }
     }
-    Ok(stacks
-        .iter()
-        .map(|x| x.last().unwrap_or(&(' ' as u8)))
-        .fold("".to_string(), |mut a, x| {
-            a.push(*x as char);
-            a
-        }))
+    Ok(tops(&stacks))
 }
 
 pub fn solve_b() -> Result<String> {
-    let mut stacks = Vec::new();
-
-    let s = read_string("inputs/day05a")?;
-    let mut lines = s.lines();
-
-    for l in &mut lines {
-        if stacks.len() == 0 {
-            for _ in 0..l.len() / 4 + 1 {
-                stacks.push(Vec::new());
-            }
-        }
-
-        if l.as_bytes()[1] == '1' as u8 {
-            break;
-        }
-
-        for i in 0..stacks.len() {
-            let c = l.as_bytes()[i * 4 + 1];
-            if c != ' ' as u8 {
-                stacks[i].push(c);
-            }
-        }
-    }
-
-    for s in stacks.iter_mut() {
-        s.reverse();
-    }
-
-    lines.next().unwrap();
-    for l in &mut lines {
-        let s = l.split(' ').collect::<Vec<_>>();
-        let n: usize = s[1].parse()?;
-        let from: usize = s[3].parse()?;
-        let to: usize = s[5].parse()?;
+    let (mut stacks, moves) = parse(&read_bytes("inputs/day05a")?)?;
 
+    for [n, from, to] in moves {
         let fsl = stacks[from - 1].len();
         let chunk = Vec::from(&stacks[from - 1][fsl - n..]);
         stacks[to - 1].extend(chunk);
         stacks[from - 1].truncate(fsl - n);
     }
-    Ok(stacks
-        .iter()
-        .map(|x| x.last().unwrap_or(&(' ' as u8)))
-        .fold("".to_string(), |mut a, x| {
-            a.push(*x as char);
-            a
-        }))
+    Ok(tops(&stacks))
 }
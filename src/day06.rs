@@ -1,7 +1,17 @@
-use crate::error::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{error::Result, util::read_bytes};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Tuning Trouble",
+    tags: &[Tag::SlidingWindow],
+    status: Status::Solved,
+};
 
 fn solve<const N: usize>() -> Result<usize> {
-    let f = std::fs::read("inputs/day06a")?;
+    let f = read_bytes("inputs/day06a")?;
     let (x, _) = f
         .windows(N)
         .enumerate()
@@ -26,3 +36,12 @@ pub fn solve_a() -> Result<usize> {
 pub fn solve_b() -> Result<usize> {
     solve::<14>()
 }
+
+/// Generates a stream of `n` characters from a three letter alphabet followed
+/// by the only marker, so both parts scan the whole input.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut s: Vec<u8> = (0..n).map(|_| rng.gen_range(b'a'..=b'c')).collect();
+    s.extend(b"abcdefghijklmnopqrstuvwxyz\n");
+    s
+}
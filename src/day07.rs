@@ -1,3 +1,4 @@
+use std::alloc::Global;
 use std::collections::HashMap;
 
 use nom::Finish;
@@ -9,7 +10,18 @@ use nom::{
     IResult,
 };
 
-use crate::{error::Result, util::read_string};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{arena::Arena, read_bytes, read_string},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "No Space Left On Device",
+    tags: &[Tag::Parsing],
+    status: Status::Solved,
+};
 
 #[derive(Debug)]
 struct Path<'a>(&'a str);
@@ -100,21 +112,118 @@ fn parse_line(i: &str) -> IResult<&str, Line> {
     ))(i)
 }
 
-enum FsNode<'a> {
-    File(u64),
-    Dir(u64, HashMap<&'a str, Box<FsNode<'a>>>),
+/// Where a tree keeps its nodes and the children of each directory.
+pub trait Store: Copy {
+    type Children<'a>
+    where
+        Self: 'a;
+
+    fn children<'a>(self) -> Self::Children<'a>
+    where
+        Self: 'a;
+
+    fn insert<'a>(self, children: &mut Self::Children<'a>, name: &'a str, node: FsNode<'a, Self>)
+    where
+        Self: 'a;
+
+    fn child<'a, 'c>(children: &'c mut Self::Children<'a>, name: &str) -> &'c mut FsNode<'a, Self>
+    where
+        Self: 'a;
+
+    fn for_each<'a>(children: &Self::Children<'a>, f: impl FnMut(&FsNode<'a, Self>))
+    where
+        Self: 'a;
+}
+
+/// One heap allocation per node and a map of children per directory.
+impl Store for Global {
+    type Children<'a>
+        = HashMap<&'a str, Box<FsNode<'a, Self>>>
+    where
+        Self: 'a;
+
+    fn children<'a>(self) -> Self::Children<'a>
+    where
+        Self: 'a,
+    {
+        HashMap::new()
+    }
+
+    fn insert<'a>(self, children: &mut Self::Children<'a>, name: &'a str, node: FsNode<'a, Self>)
+    where
+        Self: 'a,
+    {
+        children.insert(name, Box::new(node));
+    }
+
+    fn child<'a, 'c>(children: &'c mut Self::Children<'a>, name: &str) -> &'c mut FsNode<'a, Self>
+    where
+        Self: 'a,
+    {
+        children.get_mut(name).unwrap()
+    }
+
+    fn for_each<'a>(children: &Self::Children<'a>, f: impl FnMut(&FsNode<'a, Self>))
+    where
+        Self: 'a,
+    {
+        children.values().map(|n| &**n).for_each(f);
+    }
+}
+
+/// Nodes and child lists in the arena. `HashMap` cannot allocate from it, so
+/// children are kept in a list and searched linearly.
+impl Store for &Arena {
+    type Children<'a>
+        = Vec<(&'a str, Box<FsNode<'a, Self>, Self>), Self>
+    where
+        Self: 'a;
+
+    fn children<'a>(self) -> Self::Children<'a>
+    where
+        Self: 'a,
+    {
+        Vec::new_in(self)
+    }
+
+    fn insert<'a>(self, children: &mut Self::Children<'a>, name: &'a str, node: FsNode<'a, Self>)
+    where
+        Self: 'a,
+    {
+        children.push((name, Box::new_in(node, self)));
+    }
+
+    fn child<'a, 'c>(children: &'c mut Self::Children<'a>, name: &str) -> &'c mut FsNode<'a, Self>
+    where
+        Self: 'a,
+    {
+        &mut children.iter_mut().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    fn for_each<'a>(children: &Self::Children<'a>, f: impl FnMut(&FsNode<'a, Self>))
+    where
+        Self: 'a,
+    {
+        children.iter().map(|(_, n)| &**n).for_each(f);
+    }
+}
+
+pub enum FsNode<'a, S: Store + 'a> {
+    File,
+    Dir(u64, S::Children<'a>),
+}
+
+fn parse_lines(s: &str) -> impl Iterator<Item = Line<'_>> {
+    s.lines()
+        .map(|l| all_consuming(parse_line)(l).finish().unwrap().1)
 }
 
-fn parse_tree(s: &str) -> FsNode {
-    let mut tree: HashMap<&str, Box<FsNode>> = HashMap::new();
+fn parse_tree<'a, S: Store + 'a>(s: &'a str, store: S) -> FsNode<'a, S> {
+    let mut tree = store.children();
     let mut cur_dir = Vec::new();
 
     let mut total_size = 0;
-    let lines = s
-        .lines()
-        .map(|l| all_consuming(parse_line)(l).finish().unwrap().1);
-
-    for l in lines {
+    for l in parse_lines(s) {
         match l {
             Line::Command(c) => match c {
                 Command::Ls => {}
@@ -132,10 +241,10 @@ fn parse_tree(s: &str) -> FsNode {
             },
             Line::Entry(e) => match e {
                 Entry::Dir(path) => {
-                    insert_dir(&mut tree, &cur_dir, path);
+                    insert_dir(&mut tree, &cur_dir, path, store);
                 }
                 Entry::File(size, path) => {
-                    insert_file(&mut tree, &cur_dir, size, path);
+                    insert_file(&mut tree, &cur_dir, size, path, store);
                     total_size += size;
                 }
             },
@@ -144,56 +253,61 @@ fn parse_tree(s: &str) -> FsNode {
     FsNode::Dir(total_size, tree)
 }
 
-pub fn solve_a() -> Result<u64> {
-    let s = read_string("inputs/day07a")?;
-    let tree = parse_tree(&s);
+/// The directory tree on the global allocator.
+pub fn parse(input: &[u8]) -> Result<FsNode<'_, Global>> {
+    Ok(parse_tree(std::str::from_utf8(input)?, Global))
+}
+
+fn small_dirs_total<'a, S: Store + 'a>(tree: &FsNode<'a, S>) -> u64 {
     let mut dir_sizes = 0;
-    walk_dirs(&tree, &mut |n| match n {
-        FsNode::Dir(size, _) => {
+    walk_dirs(tree, &mut |n| {
+        if let FsNode::Dir(size, _) = n {
             if *size <= 100000 {
                 dir_sizes += size;
             }
         }
-        _ => {}
     });
+    dir_sizes
+}
 
-    Ok(dir_sizes)
+pub fn solve_a() -> Result<u64> {
+    let s = read_bytes("inputs/day07a")?;
+    let tree = parse(&s)?;
+    Ok(small_dirs_total(&tree))
 }
 
-fn walk_dirs(node: &FsNode, f: &mut impl FnMut(&FsNode) -> ()) {
+fn walk_dirs<'a, S: Store + 'a>(node: &FsNode<'a, S>, f: &mut impl FnMut(&FsNode<'a, S>)) {
     f(node);
     match node {
-        FsNode::File(_) => {}
-        FsNode::Dir(_size, tree) => {
-            for (_, n) in tree {
-                walk_dirs(n, f);
-            }
-        }
+        FsNode::File => {}
+        FsNode::Dir(_size, tree) => S::for_each(tree, |n| walk_dirs(n, f)),
     }
 }
 
-fn insert_dir<'a: 'b + 'c, 'b, 'c>(
-    mut tree: &'b mut HashMap<&'a str, Box<FsNode<'a>>>,
+fn insert_dir<'a: 'b + 'c, 'b, 'c, S: Store + 'a>(
+    mut tree: &'b mut S::Children<'a>,
     cur_dir: &'c Vec<&'a str>,
     path: Path<'a>,
+    store: S,
 ) {
     for p in cur_dir {
-        tree = match &mut **tree.get_mut(p).unwrap() {
+        tree = match S::child(tree, p) {
             FsNode::Dir(_subtree_size, tree) => tree,
             _ => panic!(),
         };
     }
-    tree.insert(path.0, Box::new(FsNode::Dir(0, HashMap::new())));
+    store.insert(tree, path.0, FsNode::Dir(0, store.children()));
 }
 
-fn insert_file<'a: 'b + 'c, 'b, 'c>(
-    mut tree: &'b mut HashMap<&'a str, Box<FsNode<'a>>>,
+fn insert_file<'a: 'b + 'c, 'b, 'c, S: Store + 'a>(
+    mut tree: &'b mut S::Children<'a>,
     cur_dir: &'c Vec<&'a str>,
     size: u64,
     path: Path<'a>,
+    store: S,
 ) {
     for p in cur_dir {
-        tree = match &mut **tree.get_mut(p).unwrap() {
+        tree = match S::child(tree, p) {
             FsNode::Dir(subtree_size, tree) => {
                 *subtree_size += size;
                 tree
@@ -201,12 +315,10 @@ fn insert_file<'a: 'b + 'c, 'b, 'c>(
             _ => panic!(),
         };
     }
-    tree.insert(path.0, Box::new(FsNode::File(size)));
+    store.insert(tree, path.0, FsNode::File);
 }
 
-pub fn solve_b() -> Result<u64> {
-    let s = read_string("inputs/day07a")?;
-    let tree = parse_tree(&s);
+fn smallest_freeing_dir<'a, S: Store + 'a>(tree: &FsNode<'a, S>) -> u64 {
     let needed = 30000000;
     let total = 70000000;
     let used = match tree {
@@ -217,14 +329,46 @@ pub fn solve_b() -> Result<u64> {
     let needed = needed - unused;
     let mut smallest_dir = u64::MAX;
 
-    walk_dirs(&tree, &mut |node| match node {
-        FsNode::Dir(size, _) => {
+    walk_dirs(tree, &mut |node| {
+        if let FsNode::Dir(size, _) = node {
             if *size >= needed {
                 smallest_dir = smallest_dir.min(*size);
             }
         }
-        _ => {}
     });
 
-    Ok(smallest_dir)
+    smallest_dir
+}
+
+pub fn solve_b() -> Result<u64> {
+    let s = read_bytes("inputs/day07a")?;
+    let tree = parse(&s)?;
+    Ok(smallest_freeing_dir(&tree))
+}
+
+fn trace_arena(arena: &Arena) {
+    trace::event!(
+        "arena",
+        allocated = arena.allocated(),
+        reserved = arena.reserved(),
+        chunks = arena.chunks()
+    );
+}
+
+/// [`solve_a`] with the tree in an arena.
+pub fn solve_a_arena() -> Result<u64> {
+    let s = read_string("inputs/day07a")?;
+    let arena = Arena::new();
+    let tree = parse_tree(&s, &arena);
+    trace_arena(&arena);
+    Ok(small_dirs_total(&tree))
+}
+
+/// [`solve_b`] with the tree in an arena.
+pub fn solve_b_arena() -> Result<u64> {
+    let s = read_string("inputs/day07a")?;
+    let arena = Arena::new();
+    let tree = parse_tree(&s, &arena);
+    trace_arena(&arena);
+    Ok(smallest_freeing_dir(&tree))
 }
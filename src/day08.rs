@@ -1,32 +1,30 @@
 use crate::error::Result;
-use crate::util::Vec2D;
-
-fn parse_input() -> Result<Vec2D<u8>> {
-    let mut v = std::fs::read("inputs/day08a")?;
-    let mut stride = 0;
-    for (i, x) in v.iter_mut().enumerate() {
-        if *x == '\n' as u8 {
-            if stride == 0 {
-                stride = i + 1;
-            }
-        } else {
-            *x -= '0' as u8;
-        }
-    }
-    Ok(Vec2D {
-        v,
-        stride: stride as i64,
-    })
+use crate::meta::{DayInfo, Status, Tag};
+use crate::util::{read_bytes, Grid, Point2};
+use crate::viz::{Frame, Rgb, Visualize, Viz};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Treetop Tree House",
+    tags: &[Tag::Grid],
+    status: Status::Solved,
+};
+
+/// The height of every tree.
+pub fn parse(input: &[u8]) -> Grid<u8> {
+    Grid::parse(input, b'0').map(|x| x - b'0')
 }
 
-fn is_visible(p: (i64, i64), v: &Vec2D<u8>) -> bool {
-    let (xm, ym) = v.dims();
+fn parse_input() -> Result<Grid<u8>> {
+    Ok(parse(&read_bytes("inputs/day08a")?))
+}
+
+fn is_visible(p: Point2, v: &Grid<u8>) -> bool {
     let height = v[p];
-    'next_dir: for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+    'next_dir: for d in Point2::unit_offsets() {
         let mut pn = p;
         loop {
-            pn = (pn.0 + dx, pn.1 + dy);
-            if pn.0 >= 0 && pn.0 < xm && pn.1 >= 0 && pn.1 < ym {
+            pn += d;
+            if v.contains(pn) {
                 if v[pn] >= height {
                     continue 'next_dir;
                 }
@@ -38,16 +36,15 @@ fn is_visible(p: (i64, i64), v: &Vec2D<u8>) -> bool {
     false
 }
 
-fn scenic_score(p: (i64, i64), v: &Vec2D<u8>) -> i64 {
-    let (xm, ym) = v.dims();
+fn scenic_score(p: Point2, v: &Grid<u8>) -> i64 {
     let height = v[p];
     let mut score = 1;
-    for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+    for d in Point2::unit_offsets() {
         let mut visible = 0;
         let mut pn = p;
         loop {
-            pn = (pn.0 + dx, pn.1 + dy);
-            if pn.0 >= 0 && pn.0 < xm && pn.1 >= 0 && pn.1 < ym {
+            pn += d;
+            if v.contains(pn) {
                 visible += 1;
                 if v[pn] >= height {
                     break;
@@ -69,7 +66,7 @@ pub fn solve_a() -> Result<u64> {
     let mut visible = 0;
     for x in 0..xm {
         for y in 0..ym {
-            if is_visible((x, y), &v) {
+            if is_visible(Point2::xy(x, y), &v) {
                 visible += 1;
             }
         }
@@ -80,44 +77,61 @@ pub fn solve_a() -> Result<u64> {
 
 pub fn solve_a_opt() -> Result<u64> {
     let v = parse_input()?;
+    Ok(count_visible(&v, &mut Viz::off()))
+}
 
+fn count_visible(v: &Grid<u8>, viz: &mut Viz) -> u64 {
     let (xm, ym) = v.dims();
 
-    let mut vis = Vec2D {
-        v: vec![false; (xm * ym) as usize],
-        stride: xm,
-    };
+    let mut vis = Grid::new(xm as usize, ym as usize, false);
 
     let mut visible = 0;
 
     for x in 0..xm {
-        visible += do_scan(&v, &mut vis, x, 0, 0, 1);
-        visible += do_scan(&v, &mut vis, x, xm - 1, 0, -1);
+        visible += do_scan(v, &mut vis, Point2::xy(x, 0), Point2::xy(0, 1));
+        visible += do_scan(v, &mut vis, Point2::xy(x, xm - 1), Point2::xy(0, -1));
+        viz.frame(|| render(v, |p| vis[p].then_some(Rgb::BROWN)));
     }
 
     for y in 0..ym {
-        visible += do_scan(&v, &mut vis, 0, y, 1, 0);
-        visible += do_scan(&v, &mut vis, ym - 1, y, -1, 0);
+        visible += do_scan(v, &mut vis, Point2::xy(0, y), Point2::xy(1, 0));
+        visible += do_scan(v, &mut vis, Point2::xy(ym - 1, y), Point2::xy(-1, 0));
+        viz.frame(|| render(v, |p| vis[p].then_some(Rgb::BROWN)));
     }
 
-    Ok(visible)
+    viz.show(|| {
+        render(v, |p| vis[p].then_some(Rgb::BROWN)).with_caption(format!("{visible} visible"))
+    });
+    visible
 }
 
-fn do_scan(v: &Vec2D<u8>, vis: &mut Vec2D<bool>, mut x: i64, mut y: i64, dx: i64, dy: i64) -> u64 {
+/// Renders the tree heights, `overlay` picks the background of each tree.
+fn render(v: &Grid<u8>, overlay: impl Fn(Point2) -> Option<Rgb>) -> Frame {
     let (xm, ym) = v.dims();
+    let mut frame = Frame::new(xm as usize, ym as usize);
+    for (p, &h) in v.iter() {
+        let fg = Rgb(0, 60, 0).lerp(Rgb(150, 255, 150), h as f64 / 9.0);
+        frame.set(p.x(), p.y(), (b'0' + h) as char, fg);
+        if let Some(bg) = overlay(p) {
+            frame.highlight(p.x(), p.y(), bg);
+        }
+    }
+    frame
+}
+
+fn do_scan(v: &Grid<u8>, vis: &mut Grid<bool>, mut p: Point2, d: Point2) -> u64 {
     let mut max_height_so_far = None;
     let mut visible = 0;
-    while x >= 0 && y >= 0 && x < xm && y < ym {
-        let this_height = v[(x, y)];
+    while v.contains(p) {
+        let this_height = v[p];
         if max_height_so_far.map_or(true, |h| h < this_height) {
             max_height_so_far = Some(this_height);
-            if !vis[(x, y)] {
+            if !vis[p] {
                 visible += 1;
             }
-            vis[(x, y)] = true;
+            vis[p] = true;
         }
-        x += dx;
-        y += dy;
+        p += d;
     }
     visible
 }
@@ -130,60 +144,59 @@ pub fn solve_b() -> Result<i64> {
     let mut max_score = 0;
     for x in 1..xm - 1 {
         for y in 1..ym - 1 {
-            max_score = max_score.max(scenic_score((x, y), &v));
+            max_score = max_score.max(scenic_score(Point2::xy(x, y), &v));
         }
     }
 
     Ok(max_score)
 }
 
-// pub fn solve_b_opt() -> Result<u64> {
-//     let v = parse_input()?;
-
-//     let (xm, ym) = v.dims();
-
-//     let mut maps:Vec<_> = (0..4).map(|_| Vec2D {
-//         v: vec![-1i8; (xm * ym) as usize],
-//         stride: xm,
-//     }).collect();
-
-//     for x in 0..xm {
-//         do_scan_b(&v, &mut maps, x, 0, 0, 1);
-//         do_scan_b(&v, &mut maps, x, xm-1, 0, -1);
-//     }
-
-//     for y in 0..ym {
-//         do_scan_b(&v, &mut maps, 0, y, 1, 0);
-//         do_scan_b(&v, &mut maps, ym-1, y, -1, 0);
-//     }
-
-//     for x in 0..xm {
-//         for y in 0..ym {
-
-//         }
-//     }
-
-//     Ok(visible)
-// }
-
-// fn do_scan_b(v: &Vec2D<u8>, maps: &mut Vec<Vec2D<i8>>, mut x: i64, mut y: i64, dx: i64, dy: i64) {
-//     let (xm, ym) = v.dims();
-//     let mut max_height_so_far = None;
-//     let mut visible = 0;
-//     while x >= 0 && y >= 0 && x < xm && y < ym {
-//         let this_height = v[(x, y)];
-//         if max_height_so_far.map_or(true, |h| h < this_height) {
-//             max_height_so_far = Some(this_height);
-//             if !vis[(x, y)] {
-//                 visible += 1;
-//             }
-//             vis[(x, y)] = true;
-//         }
-//         x += dx;
-//         y += dy;
-//     }
-//     visible
-// }
+/// Visibility scans for part 1, scenic scores row by row for part 2.
+pub struct Trees {
+    v: Grid<u8>,
+    part_no: usize,
+}
+
+impl Trees {
+    pub fn new(part_no: usize) -> Result<Self> {
+        Ok(Self {
+            v: parse_input()?,
+            part_no,
+        })
+    }
+}
+
+impl Visualize for Trees {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        let v = &self.v;
+        if self.part_no == 1 {
+            count_visible(v, viz);
+            return viz.finish();
+        }
+
+        let (xm, ym) = v.dims();
+        let mut scores = Grid::new(xm as usize, ym as usize, 0);
+        let mut best = (0, Point2::xy(0, 0));
+        for y in 1..ym - 1 {
+            for x in 1..xm - 1 {
+                let p = Point2::xy(x, y);
+                scores[p] = scenic_score(p, v);
+                best = best.max((scores[p], p));
+            }
+            let heat = |p: Point2| {
+                let t = (scores[p] as f64).ln_1p() / (best.0 as f64).ln_1p().max(1.0);
+                (scores[p] > 0).then(|| Rgb::BLACK.lerp(Rgb::RED, t))
+            };
+            viz.frame(|| render(v, heat));
+        }
+        viz.show(|| {
+            let mut frame = render(v, |p| (scores[p] > 0).then_some(Rgb(60, 0, 0)));
+            frame.highlight(best.1.x(), best.1.y(), Rgb::CYAN);
+            frame.with_caption(format!("best scenic score {}", best.0))
+        });
+        viz.finish()
+    }
+}
 
 #[cfg(test)]
 mod test {
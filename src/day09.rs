@@ -1,156 +1,160 @@
-use std::collections::HashSet;
-
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{digit1, newline},
-    combinator::{map, opt},
-    multi::fold_many1,
+    bytes::complete::{tag, take},
+    character::complete::newline,
+    combinator::{map, map_opt, opt},
+    multi::many1,
     sequence::{separated_pair, terminated},
     IResult,
 };
 
-use crate::error::Result;
-
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-impl From<&[u8]> for Direction {
-    fn from(value: &[u8]) -> Self {
-        use Direction::*;
-        match value[0] as char {
-            'U' => Up,
-            'D' => Down,
-            'L' => Left,
-            'R' => Right,
-            _ => panic!(),
-        }
-    }
-}
-
-impl From<Direction> for (i64, i64) {
-    fn from(value: Direction) -> Self {
-        use Direction::*;
-        match value {
-            Up => (0, 1),
-            Down => (0, -1),
-            Left => (-1, 0),
-            Right => (1, 0),
-        }
-    }
-}
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{ints::int, read_bytes, Direction, Point2, SparseGrid},
+    viz::{Frame, Rgb, Visualize, Viz},
+};
 
-fn addp(a: (i64, i64), b: (i64, i64)) -> (i64, i64) {
-    (a.0 + b.0, a.1 + b.1)
-}
+pub const INFO: DayInfo = DayInfo {
+    title: "Rope Bridge",
+    tags: &[Tag::Grid, Tag::Simulation],
+    status: Status::Solved,
+};
 
 fn parse_dir(i: &[u8]) -> IResult<&[u8], Direction> {
-    map(
-        alt((
-            tag(&['U' as u8]),
-            tag(&['D' as u8]),
-            tag(&['L' as u8]),
-            tag(&['R' as u8]),
-        )),
-        Into::into,
-    )(i)
-}
-
-fn parse_num(i: &[u8]) -> IResult<&[u8], u8> {
-    map(digit1, |x: &[u8]| {
-        let mut acc = 0;
-        for d in x.iter() {
-            acc *= 10;
-            acc += d - '0' as u8;
-        }
-        acc
-    })(i)
+    map_opt(take(1usize), |x: &[u8]| Direction::from_byte(x[0]))(i)
 }
 
-struct Line {
+pub struct Line {
     dir: Direction,
-    steps: u8,
+    steps: u32,
 }
 
 fn parse_line(i: &[u8]) -> IResult<&[u8], Line> {
     map(
         terminated(
-            separated_pair(parse_dir, tag(&[' ' as u8]), parse_num),
+            separated_pair(parse_dir, tag(&[' ' as u8]), int),
             opt(newline),
         ),
         |(dir, steps)| Line { dir, steps },
     )(i)
 }
 
-fn parse_lines<T>(
-    i: &[u8],
-    init: impl FnMut() -> T,
-    f: impl FnMut(T, Line) -> T,
-) -> IResult<&[u8], T> {
-    fold_many1(parse_line, init, f)(i)
+/// The moves of the head.
+pub fn parse(input: &[u8]) -> Vec<Line> {
+    many1(parse_line)(input).unwrap().1
 }
 
 #[derive(Debug)]
 struct State<const N: usize> {
-    pos: [(i64, i64); N],
-    visited: HashSet<(i64, i64)>,
+    pos: [Point2; N],
+    visited: SparseGrid<()>,
 }
 
-fn solve<const N: usize>() -> Result<usize> {
-    let f = std::fs::read("inputs/day09a")?;
-
-    let (_, state) = parse_lines(
-        &f,
-        || State {
-            pos: [(0, 0); N],
-            visited: {
-                let mut m = HashSet::new();
-                m.insert((0, 0));
-                m
-            },
-        },
-        |mut state, line| {
-            let dh = line.dir.into();
-
-            for _ in 0..line.steps {
-                state.pos[0] = addp(state.pos[0], dh);
-
-                for i in 1..state.pos.len() {
-                    let h_pos = state.pos[i - 1];
-                    let t_pos = &mut state.pos[i];
-
-                    loop {
-                        let dx = h_pos.0 - t_pos.0;
-                        let dy = h_pos.1 - t_pos.1;
-                        if dx.abs() >= 2 || dy.abs() >= 2 {
-                            t_pos.0 += dx.signum();
-                            t_pos.1 += dy.signum();
-                        } else {
-                            break;
-                        }
-                    }
+/// Renders a `w` by `h` window with its top left corner at `origin`.
+fn render(pos: &[Point2], visited: &SparseGrid<()>, origin: Point2, (w, h): (i64, i64)) -> Frame {
+    let mut frame = Frame::new(w as usize, h as usize);
+    let to_frame = |p: Point2| (p.x() - origin.x(), p.y() - origin.y());
+    for y in 0..h {
+        for x in 0..w {
+            frame.set(x, y, '.', Rgb(60, 60, 60));
+        }
+    }
+    for p in visited.positions() {
+        let (x, y) = to_frame(p);
+        frame.set(x, y, '#', Rgb::GRAY);
+    }
+    for (i, &p) in pos.iter().enumerate().rev() {
+        let (x, y) = to_frame(p);
+        let ch = match i {
+            0 => 'H',
+            i if i == pos.len() - 1 => 'T',
+            i => (b'0' + i as u8) as char,
+        };
+        frame.set(x, y, ch, if i == 0 { Rgb::RED } else { Rgb::YELLOW });
+    }
+    frame
+}
+
+fn solve<const N: usize>(viz: &mut Viz) -> Result<usize> {
+    let mut state = State {
+        pos: [Point2::default(); N],
+        visited: SparseGrid::from_iter([(Point2::default(), ())]),
+    };
+    for line in parse(&read_bytes("inputs/day09a")?) {
+        let dh = line.dir.offset();
+
+        for _ in 0..line.steps {
+            state.pos[0] += dh;
+
+            for i in 1..state.pos.len() {
+                let h_pos = state.pos[i - 1];
+                let t_pos = &mut state.pos[i];
+
+                while h_pos.chebyshev(*t_pos) >= 2 {
+                    *t_pos += (h_pos - *t_pos).signum();
                 }
-                state.visited.insert(state.pos[state.pos.len() - 1]);
             }
+            state.visited.insert(state.pos[state.pos.len() - 1], ());
+            viz.frame(|| {
+                let head = state.pos[0];
+                let origin = head - Point2::xy(40, 20);
+                render(&state.pos, &state.visited, origin, (80, 40))
+                    .with_caption(format!("{} visited", state.visited.len()))
+            });
+        }
+    }
 
-            state
-        },
-    )
-    .unwrap();
+    viz.show(|| {
+        let (origin, _) = state.visited.bounds().unwrap();
+        render(&state.pos, &state.visited, origin, state.visited.dims())
+            .with_caption(format!("{} visited", state.visited.len()))
+    });
 
     Ok(state.visited.len())
 }
 
 pub fn solve_a() -> Result<usize> {
-    solve::<2>()
+    solve::<2>(&mut Viz::off())
 }
 
 pub fn solve_b() -> Result<usize> {
-    solve::<10>()
+    solve::<10>(&mut Viz::off())
+}
+
+/// The rope with the camera following its head.
+pub struct Rope {
+    part_no: usize,
+}
+
+impl Rope {
+    pub fn new(part_no: usize) -> Result<Self> {
+        Ok(Self { part_no })
+    }
+}
+
+impl Visualize for Rope {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        if self.part_no == 1 {
+            solve::<2>(viz)?;
+        } else {
+            solve::<10>(viz)?;
+        }
+        viz.finish()
+    }
+}
+
+/// Generates `n` random moves of up to 20 steps.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut s = String::new();
+    for _ in 0..n {
+        let dir = ['U', 'D', 'L', 'R'][rng.gen_range(0..4)];
+        s += &format!("{} {}\n", dir, rng.gen_range(1..=20));
+    }
+    s.into_bytes()
 }
 
 #[cfg(test)]
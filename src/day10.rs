@@ -1,33 +1,31 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{digit1, newline},
+    character::complete::newline,
     combinator::{iterator, map, opt},
-    sequence::{preceded, terminated, tuple},
+    sequence::{preceded, terminated},
     IResult,
 };
 
-use crate::error::Result;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{ints::int, read_bytes},
+};
 
-fn parse_num(i: &[u8]) -> IResult<&[u8], i64> {
-    map(
-        |i| tuple((opt(tag(b"-")), digit1))(i),
-        |(sign, x): (_, &[u8])| {
-            let mut acc: i64 = 0;
-            for d in x.iter() {
-                acc *= 10;
-                acc += (d - '0' as u8) as i64;
-            }
-            acc * if sign.is_some() { -1 } else { 1 }
-        },
-    )(i)
-}
+pub const INFO: DayInfo = DayInfo {
+    title: "Cathode-Ray Tube",
+    tags: &[Tag::Parsing, Tag::Simulation],
+    // the CRT picture only goes to a trace snapshot
+    status: Status::Stubbed(2),
+};
 
 #[derive(Debug)]
 struct Addx(i64);
 
 fn parse_addx(i: &[u8]) -> IResult<&[u8], Addx> {
-    map(preceded(tag(b"addx "), parse_num), Addx)(i)
+    map(preceded(tag(b"addx "), int), Addx)(i)
 }
 
 #[derive(Debug)]
@@ -38,7 +36,7 @@ fn parse_noop(i: &[u8]) -> IResult<&[u8], Noop> {
 }
 
 #[derive(Debug)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     Addx(i64),
 }
@@ -66,6 +64,11 @@ fn parse_line(i: &[u8]) -> IResult<&[u8], Instruction> {
     )(i)
 }
 
+/// The instructions up to the first one that does not parse.
+pub fn parse(input: &[u8]) -> Vec<Instruction> {
+    iterator(input, parse_line).collect()
+}
+
 #[derive(Debug, Clone, Copy)]
 struct State {
     acc: i64,
@@ -96,11 +99,10 @@ impl<T> Iterator for TwoIterable<T> {
 }
 
 pub fn solve_a() -> Result<i64> {
-    let f = std::fs::read("inputs/day10a")?;
+    let f = read_bytes("inputs/day10a")?;
 
     let mut s = State { acc: 1 };
-    let mut pit = iterator(&f[..], parse_line);
-    let it = pit.flat_map(|ins| match ins {
+    let it = parse(&f).into_iter().flat_map(|ins| match ins {
         Instruction::Noop => TwoIterable::OneElem(s),
         Instruction::Addx(v) => {
             let prev_s = s.clone();
@@ -121,9 +123,7 @@ pub fn solve_a() -> Result<i64> {
         })
         .sum();
 
-    // dbg!(pit.finish());
-
-    // dbg!(&solution);
+    trace::event!("signal strength", sum = solution);
 
     Ok(solution)
 }
@@ -200,11 +200,10 @@ pub fn solve_a() -> Result<i64> {
 // }
 
 pub fn solve_b() -> Result<usize> {
-    let f = std::fs::read("inputs/day10a")?;
+    let f = read_bytes("inputs/day10a")?;
 
     let mut s = State { acc: 1 };
-    let mut pit = iterator(&f[..], parse_line);
-    let it = pit.flat_map(|ins| match ins {
+    let it = parse(&f).into_iter().flat_map(|ins| match ins {
         Instruction::Noop => TwoIterable::OneElem(s),
         Instruction::Addx(v) => {
             let prev_s = s.clone();
@@ -222,12 +221,16 @@ pub fn solve_b() -> Result<usize> {
         }
     });
 
-    // for y in 0..6 {
-    //     for x in 0..40 {
-    //         print!("{}", if crt[y * 40 + x] { '#' } else { '.' });
-    //     }
-    //     println!("");
-    // }
+    trace::snapshot!(
+        "crt",
+        crt.chunks(40)
+            .map(|l| l
+                .iter()
+                .map(|x| if *x { '#' } else { '.' })
+                .collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
 
     Ok(0)
 }
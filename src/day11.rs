@@ -2,15 +2,32 @@ use std::collections::VecDeque;
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
+    bytes::complete::tag,
     character::complete::{newline, space1},
-    combinator::{all_consuming, map, opt},
+    combinator::{all_consuming, map},
     multi::separated_list0,
-    sequence::{delimited, preceded, terminated, tuple},
+    sequence::{delimited, preceded, tuple},
     Finish, IResult,
 };
 
-use crate::{error::Result, util::parse_num};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{
+        cycle,
+        input::{blocks, normalize},
+        ints::int,
+        num::lcm_all,
+        read_bytes,
+    },
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Monkey in the Middle",
+    tags: &[Tag::Parsing, Tag::Simulation, Tag::CycleDetection],
+    status: Status::Solved,
+};
 
 #[derive(Debug, Clone, Copy)]
 enum Operation {
@@ -34,7 +51,7 @@ impl Operand {
 }
 
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     items: VecDeque<i64>,
     operation: Operation,
     test: i64,
@@ -60,14 +77,11 @@ impl Operation {
 }
 
 fn item_list(i: &[u8]) -> IResult<&[u8], VecDeque<i64>> {
-    map(separated_list0(tag(b", "), parse_num), Into::into)(i)
+    map(separated_list0(tag(b", "), int), Into::into)(i)
 }
 
 fn parse_operand(i: &[u8]) -> IResult<&[u8], Operand> {
-    alt((
-        map(tag(b"old"), |_| Operand::Old),
-        map(parse_num, Operand::Const),
-    ))(i)
+    alt((map(tag(b"old"), |_| Operand::Old), map(int, Operand::Const)))(i)
 }
 
 fn parse_operation(i: &[u8]) -> IResult<&[u8], Operation> {
@@ -88,7 +102,7 @@ fn parse_operation(i: &[u8]) -> IResult<&[u8], Operation> {
 fn parse_monkey(i: &[u8]) -> IResult<&[u8], Monkey> {
     map(
         tuple((
-            delimited(tag(b"Monkey "), parse_num, tag(b":\n")),
+            delimited(tag(b"Monkey "), int::<usize>, tag(b":\n")),
             delimited(
                 tuple((space1, tag(b"Starting items: "))),
                 item_list,
@@ -99,20 +113,13 @@ fn parse_monkey(i: &[u8]) -> IResult<&[u8], Monkey> {
                 parse_operation,
                 newline,
             ),
-            delimited(
-                tuple((space1, tag(b"Test: divisible by "))),
-                parse_num,
-                newline,
-            ),
+            delimited(tuple((space1, tag(b"Test: divisible by "))), int, newline),
             delimited(
                 tuple((space1, tag(b"If true: throw to monkey "))),
-                parse_num,
+                int,
                 newline,
             ),
-            preceded(
-                tuple((space1, tag(b"If false: throw to monkey "))),
-                parse_num,
-            ),
+            preceded(tuple((space1, tag(b"If false: throw to monkey "))), int),
         )),
         |(_monkey_id, items, operation, test, action_true, action_false)| Monkey {
             items,
@@ -124,89 +131,63 @@ fn parse_monkey(i: &[u8]) -> IResult<&[u8], Monkey> {
     )(i)
 }
 
-fn parse_monkeys(i: &[u8]) -> IResult<&[u8], Vec<Monkey>> {
-    all_consuming(separated_list0(
-        take_while1(|x: u8| x.is_ascii_whitespace()),
-        terminated(parse_monkey, opt(newline)),
-    ))(i)
+/// The monkeys in the order they are listed.
+pub fn parse(i: &[u8]) -> Vec<Monkey> {
+    let i = normalize(i);
+    blocks(&i)
+        .map(|block| all_consuming(parse_monkey)(block).finish().unwrap().1)
+        .collect()
 }
 
 pub fn solve<const DIV: bool>(n: i64) -> Result<i64> {
-    let f = std::fs::read("inputs/day11a")?;
-
-    let monkeys = parse_monkeys(&f[..]).finish().unwrap().1;
-
-    let mut tortoise = monkeys.clone();
-    let mut hare = monkeys.clone();
-    let mut inspections_tortoise = vec![0; tortoise.len()];
-    let mut inspections_hare = vec![0; hare.len()];
-    let modulus: i64 = tortoise.iter().map(|x| x.test).product();
-
-    sim_round::<DIV>(&mut tortoise, &mut inspections_tortoise, modulus);
-    sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-    sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-    while tortoise != hare {
-        sim_round::<DIV>(&mut tortoise, &mut inspections_tortoise, modulus);
-        sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-        sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-    }
-
-    let mut mu = 0;
-    tortoise = monkeys.clone();
-    inspections_tortoise = vec![0; tortoise.len()];
-
-    while tortoise != hare {
-        sim_round::<DIV>(&mut tortoise, &mut inspections_tortoise, modulus);
-        sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-        mu += 1;
-    }
-
-    let mut lam = 1;
-    hare = tortoise.clone();
-    inspections_hare = vec![0; hare.len()];
-    sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-    while tortoise != hare {
-        sim_round::<DIV>(&mut hare, &mut inspections_hare, modulus);
-        lam += 1;
-    }
-
-    let n = n - mu;
-    let skipped_cycles = n / lam;
-    let skipped_iters = skipped_cycles * lam;
-    inspections_tortoise
-        .iter_mut()
-        .zip(inspections_hare.iter())
-        .for_each(|(x, y)| *x += *y * skipped_cycles);
-    let iters_left = n - skipped_iters;
-    for _ in 0..iters_left {
-        sim_round::<DIV>(&mut tortoise, &mut inspections_tortoise, modulus);
-    }
-
-    inspections_tortoise.sort();
+    let f = read_bytes("inputs/day11a")?;
+
+    let monkeys = parse(&f);
+
+    let modulus = lcm_all(monkeys.iter().map(|x| x.test));
+    trace::event!("modulus", lcm = modulus);
+
+    // one round per step, yielding the inspections of each monkey
+    let round = |monkeys: &mut Vec<Monkey>| {
+        let mut inspections = vec![0; monkeys.len()];
+        sim_round::<DIV>(monkeys, &mut inspections, modulus);
+        inspections
+    };
+    let e = cycle::extrapolate(round, monkeys, n as usize, |total, inspections, times| {
+        total.resize(inspections.len(), 0);
+        for (x, y) in total.iter_mut().zip(inspections) {
+            *x += y * times as i64;
+        }
+    });
+    trace::event!("cycle", offset = e.cycle.offset, length = e.cycle.length);
 
-    Ok(inspections_tortoise[inspections_tortoise.len() - 1]
-        * inspections_tortoise[inspections_tortoise.len() - 2])
+    let mut inspections = e.total;
+    inspections.sort();
+    Ok(inspections[inspections.len() - 1] * inspections[inspections.len() - 2])
 }
 
 pub fn solve_a() -> Result<i64> {
-    let f = std::fs::read("inputs/day11a")?;
-    let mut monkeys = parse_monkeys(&f[..]).finish().unwrap().1;
+    let f = read_bytes("inputs/day11a")?;
+    let mut monkeys = parse(&f);
     let mut inspections = vec![0; monkeys.len()];
-    let modulus: i64 = monkeys.iter().map(|x| x.test).product();
+    let modulus = lcm_all(monkeys.iter().map(|x| x.test));
 
-    for _ in 0..20 {
+    for round in 0..20 {
         sim_round::<true>(&mut monkeys, &mut inspections, modulus);
+        trace::snapshot!(&format!("round {}", round + 1), render_monkeys(&monkeys));
     }
 
     inspections.sort();
     Ok(inspections[inspections.len() - 1] * inspections[inspections.len() - 2])
 }
 
-#[allow(unused)]
-fn print_monkeys(monkeys: &[Monkey]) {
-    for (i, m) in monkeys.iter().enumerate() {
-        println!("Monkey {}: {:?}", i, m.items);
-    }
+fn render_monkeys(monkeys: &[Monkey]) -> String {
+    monkeys
+        .iter()
+        .enumerate()
+        .map(|(i, m)| format!("Monkey {}: {:?}", i, m.items))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 fn sim_round<const DIV: bool>(monkeys: &mut [Monkey], inspections: &mut [i64], modulus: i64) {
@@ -222,6 +203,8 @@ fn sim_round<const DIV: bool>(monkeys: &mut [Monkey], inspections: &mut [i64], m
             if DIV {
                 wl /= 3;
             }
+            // every test divides the modulus, so without the division by 3
+            // reducing the worry level never changes where an item goes
             wl %= modulus;
 
             let next = if wl % monkey.test == 0 {
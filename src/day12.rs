@@ -1,155 +1,156 @@
 use crate::error::Result;
-use crate::util::l_infty;
-use std::{
-    collections::{BTreeSet, HashMap},
-    io::BufRead,
+use crate::meta::{DayInfo, Status, Tag};
+use crate::trace;
+use crate::util::search::{self, Explored};
+use crate::util::{read_bytes, Grid, Point2};
+use crate::viz::{Frame, Rgb, Visualize, Viz};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Hill Climbing Algorithm",
+    tags: &[Tag::Grid, Tag::GraphSearch],
+    status: Status::Solved,
 };
 
-#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Copy, Clone)]
-struct Node {
-    priority: i64,
-    steps: i64,
-    pos: (i64, i64),
+/// Walking the height map, climbing at most one unit per step, or with
+/// `reverse` descending at most one unit per step.
+struct Climb<'a> {
+    m: &'a Grid<u8>,
+    reverse: bool,
+    target: Point2,
+    viz: &'a mut Viz,
 }
 
-fn pathfind(
-    m: &Vec<Vec<u8>>,
-    start: (i64, i64),
-    target: impl Fn(Node) -> bool,
-    target_heuristic: impl Fn((i64, i64), Node) -> i64,
-    height_check_inverse: bool,
-) -> Option<i64> {
-    let mut heap = BTreeSet::new();
-
-    let mut costs = HashMap::new();
-    // let mut visited = HashSet::new();
-
-    heap.insert(Node {
-        priority: 0,
-        steps: 0,
-        pos: start,
-    });
-    costs.insert(start, 0);
-
-    while let Some(item) = heap.pop_first() {
-        // visited.insert(item.pos);
-
-        if target(item) {
-            return Some(item.steps);
-        }
+impl search::Problem for Climb<'_> {
+    type State = Point2;
 
-        let h = m[item.pos.1 as usize][item.pos.0 as usize];
-        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let newpos = (item.pos.0 + dx, item.pos.1 + dy);
-            if newpos.0 >= 0
-                && (newpos.0 as usize) < m[0].len()
-                && newpos.1 >= 0
-                && (newpos.1 as usize) < m.len()
-            {
-                let h2 = m[newpos.1 as usize][newpos.0 as usize];
-                let n = Node {
-                    pos: newpos,
-                    priority: target_heuristic(newpos, item),
-                    steps: item.steps + 1,
-                };
-                let mut h = h;
-                let mut h2 = h2;
-                if height_check_inverse {
-                    std::mem::swap(&mut h, &mut h2);
-                }
-                if h2 <= h + 1 {
-                    if !costs.contains_key(&newpos) || costs[&newpos] > n.steps {
-                        costs.insert(newpos, n.steps);
-                        heap.replace(n);
-                    }
-                }
+    fn neighbors(&mut self, &pos: &Point2, out: &mut Vec<(Point2, i64)>) {
+        let h = self.m[pos];
+        for newpos in self.m.neighbors4(pos) {
+            let (mut h, mut h2) = (h, self.m[newpos]);
+            if self.reverse {
+                std::mem::swap(&mut h, &mut h2);
             }
+            if h2 <= h + 1 {
+                out.push((newpos, 1));
+            }
+        }
+    }
+
+    fn is_goal(&self, &pos: &Point2) -> bool {
+        if self.reverse {
+            self.m[pos] == b'a'
+        } else {
+            pos == self.target
+        }
+    }
+
+    fn heuristic(&self, &pos: &Point2) -> i64 {
+        pos.manhattan(self.target)
+    }
+
+    fn visit(
+        &mut self,
+        &pos: &Point2,
+        explored: &Explored<Point2>,
+        _frontier: &mut dyn Iterator<Item = &Point2>,
+    ) {
+        let steps = explored.cost_to(&pos).unwrap();
+        trace::event!("pop", pos = pos, steps = steps, stats = explored.stats);
+        trace::snapshot!("visited", render(self.m, explored, pos));
+        self.viz
+            .frame(|| render(self.m, explored, pos).with_caption(format!("{} steps", steps)));
+    }
+}
+
+/// Renders the height map with all positions that have been reached so far
+/// highlighted.
+fn render(m: &Grid<u8>, explored: &Explored<Point2>, pos: Point2) -> Frame {
+    let mut frame = Frame::new(m.width(), m.height());
+    for (p, &h) in m.iter() {
+        let fg = Rgb::BROWN.lerp(Rgb::WHITE, (h - b'a') as f64 / 25.0);
+        frame.set(p.x(), p.y(), h as char, fg);
+        if explored.contains(&p) {
+            frame.highlight(p.x(), p.y(), Rgb(0, 90, 0));
         }
-        // println!("\x1b[2J");
-        // println!("{} {}", item.pos.0, item.pos.1);
-        // for y in 0..m.len() {
-        //     for x in 0..m[0].len() {
-        //         print!(
-        //             "{}{}{}",
-        //             if visited.contains(&(x as i64, y as i64)) {
-        //                 "\x1b[1;42m"
-        //             } else {
-        //                 ""
-        //             },
-        //             m[y][x] as char,
-        //             if visited.contains(&(x as i64, y as i64)) {
-        //                 "\x1b[0m"
-        //             } else {
-        //                 ""
-        //             },
-        //         );
-        //     }
-        //     println!("");
-        // }
-        // println!("{:?}", &heap);
-        // println!("");
     }
+    frame.highlight(pos.x(), pos.y(), Rgb::RED);
+    frame
+}
 
-    None
+pub struct Input {
+    m: Grid<u8>,
+    start: Point2,
+    target: Point2,
 }
 
-struct Input {
-    m: Vec<Vec<u8>>,
-    start: (i64, i64),
-    target: (i64, i64),
+/// The height map with the start and target marked.
+pub fn parse(input: &[u8]) -> Result<Input> {
+    let mut m = Grid::parse(input.trim_ascii(), b' ');
+    let start = m.find(|x| *x == b'S').ok_or("no start")?;
+    let target = m.find(|x| *x == b'E').ok_or("no target")?;
+    m[start] = b'a';
+    m[target] = b'z';
+    Ok(Input { m, start, target })
 }
 
 fn parse_input() -> Result<Input> {
-    let mut m: Vec<Vec<u8>> = Vec::new();
-    let mut start = None;
-    let mut target = None;
-    for (y, line) in std::fs::read("inputs/day12a")?
-        .trim_ascii()
-        .lines()
-        .enumerate()
-    {
-        let mut v = line?.as_bytes().to_vec();
-        v.iter().position(|x| *x == 'S' as u8).map(|x| {
-            start = Some((x, y));
-            v[x] = 'a' as u8;
-        });
-        v.iter().position(|x| *x == 'E' as u8).map(|x| {
-            target = Some((x, y));
-            v[x] = 'z' as u8;
-        });
-        m.push(v);
-    }
-    let start = start.unwrap();
-    let target = target.unwrap();
-    Ok(Input {
-        m,
-        start: (start.0 as i64, start.1 as i64),
-        target: (target.0 as i64, target.1 as i64),
-    })
+    parse(&read_bytes("inputs/day12a")?)
+}
+
+/// Shortest path from the start to the target, or with `reverse` from the
+/// target down to any square of height `a`.
+fn hike(input: &Input, reverse: bool, viz: &mut Viz) -> Option<i64> {
+    let mut climb = Climb {
+        m: &input.m,
+        reverse,
+        target: input.target,
+        viz,
+    };
+    let _phase = trace::phase!("search");
+    let explored = if reverse {
+        search::bfs(&mut climb, input.target)
+    } else {
+        search::astar(&mut climb, input.start)
+    };
+    trace::event!("done", cost = explored.cost(), stats = explored.stats);
+    let goal = *explored.goal()?;
+    let steps = explored.cost()?;
+    climb
+        .viz
+        .show(|| render(&input.m, &explored, goal).with_caption(format!("{} steps", steps)));
+    Some(steps)
 }
 
 pub fn solve_a() -> Result<i64> {
     let input = parse_input()?;
-    let steps = pathfind(
-        &input.m,
-        input.start,
-        |x| x.pos == input.target,
-        |newpos, item| l_infty(newpos, input.target) + item.steps + 1,
-        false,
-    );
-    Ok(steps.unwrap())
+    Ok(hike(&input, false, &mut Viz::off()).unwrap())
 }
 
 pub fn solve_b() -> Result<i64> {
     let input = parse_input()?;
-    Ok(pathfind(
-        &input.m,
-        input.target,
-        |n| input.m[n.pos.1 as usize][n.pos.0 as usize] == 'a' as u8,
-        |_newpos, item| item.steps,
-        true,
-    )
-    .unwrap())
+    Ok(hike(&input, true, &mut Viz::off()).unwrap())
+}
+
+/// The search frontier spreading over the height map.
+pub struct Hike {
+    input: Input,
+    reverse: bool,
+}
+
+impl Hike {
+    pub fn new(part_no: usize) -> Result<Self> {
+        Ok(Self {
+            input: parse_input()?,
+            reverse: part_no == 2,
+        })
+    }
+}
+
+impl Visualize for Hike {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        hike(&self.input, self.reverse, viz);
+        viz.finish()
+    }
 }
 
 #[cfg(test)]
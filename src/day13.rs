@@ -1,110 +1,176 @@
+use std::alloc::{Allocator, Global};
 use std::cmp::Ordering;
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::newline,
-    combinator::map,
-    multi::separated_list0,
-    sequence::{delimited, tuple},
-    IResult,
+    combinator::{all_consuming, map},
+    Finish, IResult,
 };
 
-use crate::{error::Result, util::parse_num};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{
+        arena::Arena,
+        input::{blocks, lines},
+        ints::int,
+        read_bytes,
+    },
+};
 
-#[derive(Debug, Clone)]
-struct List(Vec<Item>);
+pub const INFO: DayInfo = DayInfo {
+    title: "Distress Signal",
+    tags: &[Tag::Parsing],
+    status: Status::Solved,
+};
+
+type List<A> = Vec<Item<A>, A>;
 
 #[derive(Debug, Clone)]
-enum Item {
-    List(Vec<Item>),
+pub enum Item<A: Allocator = Global> {
+    List(List<A>),
     Int(i64),
 }
 
-fn parse_list(i: &[u8]) -> IResult<&[u8], List> {
-    delimited(
-        tag(b"["),
-        map(separated_list0(tag(b","), parse_item), List),
-        tag(b"]"),
-    )(i)
+/// The list at the start of `i`, with all lists allocated by `alloc`.
+fn parse_list<A: Allocator + Clone>(i: &[u8], alloc: A) -> IResult<&[u8], List<A>> {
+    let (mut i, _) = tag(b"[")(i)?;
+    let mut list = Vec::new_in(alloc.clone());
+    if let Ok((i, _)) = tag::<_, _, ()>(b"]")(i) {
+        return Ok((i, list));
+    }
+    loop {
+        let (rest, item) = parse_item(i, alloc.clone())?;
+        list.push(item);
+        let (rest, end) = alt((tag(b","), tag(b"]")))(rest)?;
+        if end == b"]" {
+            return Ok((rest, list));
+        }
+        i = rest;
+    }
 }
 
-fn parse_item(i: &[u8]) -> IResult<&[u8], Item> {
+fn parse_item<A: Allocator + Clone>(i: &[u8], alloc: A) -> IResult<&[u8], Item<A>> {
     alt((
-        map(parse_list, |x| Item::List(x.0)),
-        map(parse_num, Item::Int),
+        map(|i| parse_list(i, alloc.clone()), Item::List),
+        map(int, Item::Int),
     ))(i)
 }
 
-fn parse_pair(i: &[u8]) -> IResult<&[u8], (Item, Item)> {
-    map(tuple((parse_list, newline, parse_list)), |(a, _, b)| {
-        (Item::List(a.0), Item::List(b.0))
-    })(i)
+fn parse_packet<A: Allocator + Clone>(i: &[u8], alloc: A) -> Item<A> {
+    let (_, list) = all_consuming(|i| parse_list(i, alloc.clone()))(i)
+        .finish()
+        .unwrap();
+    Item::List(list)
 }
 
-fn parse_pairs(i: &[u8]) -> IResult<&[u8], Vec<(Item, Item)>> {
-    separated_list0(tuple((newline, newline)), parse_pair)(i)
+fn parse_pairs<A: Allocator + Clone>(i: &[u8], alloc: A) -> Vec<(Item<A>, Item<A>), A> {
+    let mut pairs = Vec::new_in(alloc.clone());
+    for block in blocks(i) {
+        let mut packets = lines(block).map(|l| parse_packet(l, alloc.clone()));
+        pairs.push((packets.next().unwrap(), packets.next().unwrap()));
+    }
+    pairs
 }
 
-fn parse_packets(i: &[u8]) -> IResult<&[u8], Vec<Item>> {
-    separated_list0(
-        alt((map(tuple((newline, newline)), |_| ()), map(newline, |_| ()))),
-        map(parse_list, |x| Item::List(x.0)),
-    )(i)
+/// The pairs of packets with the global allocator.
+pub fn parse(i: &[u8]) -> Vec<(Item, Item)> {
+    parse_pairs(i, Global)
 }
 
-impl PartialOrd for Item {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+fn parse_packets<A: Allocator + Clone>(i: &[u8], alloc: A) -> List<A> {
+    let mut packets = Vec::new_in(alloc.clone());
+    for l in lines(i).filter(|l| !l.is_empty()) {
+        packets.push(parse_packet(l, alloc.clone()));
+    }
+    packets
+}
+
+impl<A: Allocator> Ord for Item<A> {
+    /// An integer compares like a list holding only that integer.
+    fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
-            (Item::Int(a), Item::Int(b)) => a.partial_cmp(b),
-            (Item::List(a), Item::List(b)) => a.partial_cmp(b),
-            (Item::Int(a), b) => Item::List(vec![Item::Int(*a)]).partial_cmp(b),
-            (a, Item::Int(b)) => a.partial_cmp(&Item::List(vec![Item::Int(*b)])),
+            (Item::Int(a), Item::Int(b)) => a.cmp(b),
+            (Item::List(a), Item::List(b)) => a.iter().cmp(b.iter()),
+            (Item::Int(_), Item::List(b)) => std::slice::from_ref(self).iter().cmp(b.iter()),
+            (Item::List(a), Item::Int(_)) => a.iter().cmp(std::slice::from_ref(other).iter()),
         }
     }
 }
 
-impl Ord for Item {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl<A: Allocator> PartialOrd for Item<A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl PartialEq for Item {
+impl<A: Allocator> PartialEq for Item<A> {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Item::List(a), Item::List(b)) => a == b,
-            (Item::List(a), Item::Int(b)) => *a == vec![Item::Int(*b)],
-            (Item::Int(a), Item::List(b)) => vec![Item::Int(*a)] == *b,
-            (Item::Int(a), Item::Int(b)) => a == b,
-        }
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Eq for Item {}
+impl<A: Allocator> Eq for Item<A> {}
 
-pub fn solve_a() -> Result<i64> {
-    let f = std::fs::read("inputs/day13a")?;
+fn ordered_pairs<A: Allocator + Clone>(f: &[u8], alloc: A) -> i64 {
     let mut idx_sum = 0;
-    for (i, (l, r)) in parse_pairs(&f[..]).unwrap().1.iter().enumerate() {
+    for (i, (l, r)) in parse_pairs(f, alloc).iter().enumerate() {
         if l < r {
             idx_sum += (i + 1) as i64;
         }
     }
-    Ok(idx_sum)
+    idx_sum
 }
 
-pub fn solve_b() -> Result<i64> {
-    let f = std::fs::read("inputs/day13a")?;
-    let mut packets = parse_packets(&f[..]).unwrap().1;
-    let two = Item::List(vec![Item::List(vec![Item::Int(2)])]);
-    let six = Item::List(vec![Item::List(vec![Item::Int(6)])]);
+fn decoder_key<A: Allocator + Clone>(f: &[u8], alloc: A) -> i64 {
+    let mut packets = parse_packets(f, alloc.clone());
+    let two = parse_packet(b"[[2]]", alloc.clone());
+    let six = parse_packet(b"[[6]]", alloc.clone());
     packets.push(two.clone());
     packets.push(six.clone());
     packets.sort();
     let a = packets.iter().position(|x| *x == two).unwrap() as i64 + 1;
     let b = packets.iter().position(|x| *x == six).unwrap() as i64 + 1;
-    Ok(a * b)
+    a * b
+}
+
+pub fn solve_a() -> Result<i64> {
+    let f = read_bytes("inputs/day13a")?;
+    Ok(ordered_pairs(&f, Global))
+}
+
+pub fn solve_b() -> Result<i64> {
+    let f = read_bytes("inputs/day13a")?;
+    Ok(decoder_key(&f, Global))
+}
+
+fn trace_arena(arena: &Arena) {
+    trace::event!(
+        "arena",
+        allocated = arena.allocated(),
+        reserved = arena.reserved(),
+        chunks = arena.chunks()
+    );
+}
+
+/// [`solve_a`] with the packets in an arena.
+pub fn solve_a_arena() -> Result<i64> {
+    let f = read_bytes("inputs/day13a")?;
+    let arena = Arena::new();
+    let idx_sum = ordered_pairs(&f, &arena);
+    trace_arena(&arena);
+    Ok(idx_sum)
+}
+
+/// [`solve_b`] with the packets in an arena.
+pub fn solve_b_arena() -> Result<i64> {
+    let f = read_bytes("inputs/day13a")?;
+    let arena = Arena::new();
+    let key = decoder_key(&f, &arena);
+    trace_arena(&arena);
+    Ok(key)
 }
 
 #[cfg(test)]
@@ -120,4 +186,10 @@ mod test {
     fn test_b() {
         assert_eq!(solve_b().unwrap(), 22344);
     }
+
+    #[test]
+    fn test_arena() {
+        assert_eq!(solve_a_arena().unwrap(), 5198);
+        assert_eq!(solve_b_arena().unwrap(), 22344);
+    }
 }
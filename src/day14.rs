@@ -3,23 +3,37 @@ use nom::{
     sequence::tuple, IResult,
 };
 
+use crate::meta::{DayInfo, Status, Tag};
 use crate::{
     error::Result,
-    util::{parse_num, Vec2D},
+    trace,
+    util::{ints::int, read_bytes, Grid, Point2},
+    viz::{Frame, Rgb, Visualize, Viz},
 };
 
-fn parse_coord(i: &[u8]) -> IResult<&[u8], (i64, i64)> {
-    map(tuple((parse_num, tag(b","), parse_num)), |(a, _, b)| (a, b))(i)
+pub const INFO: DayInfo = DayInfo {
+    title: "Regolith Reservoir",
+    tags: &[Tag::Grid, Tag::Simulation],
+    status: Status::Solved,
+};
+
+fn parse_coord(i: &[u8]) -> IResult<&[u8], Point2> {
+    map(tuple((int, tag(b","), int)), |(a, _, b)| Point2::xy(a, b))(i)
 }
 
-fn parse_coords(i: &[u8]) -> IResult<&[u8], Vec<(i64, i64)>> {
+fn parse_coords(i: &[u8]) -> IResult<&[u8], Vec<Point2>> {
     separated_list0(tag(b" -> "), parse_coord)(i)
 }
 
-fn parse_scans(i: &[u8]) -> IResult<&[u8], Vec<Vec<(i64, i64)>>> {
+fn parse_scans(i: &[u8]) -> IResult<&[u8], Vec<Vec<Point2>>> {
     separated_list0(newline, parse_coords)(i)
 }
 
+/// The rock paths of the scan.
+pub fn parse(i: &[u8]) -> Vec<Vec<Point2>> {
+    parse_scans(i).unwrap().1
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Block {
     Air,
@@ -27,66 +41,65 @@ enum Block {
     Sand,
 }
 
-fn solve(mut scans: Vec<Vec<(i64, i64)>>, add_floor: bool) -> i64 {
-    // let min_x = scans
-    //     .iter()
-    //     .flat_map(|s| s.iter().map(|x| x.0).min())
-    //     .min()
-    //     .unwrap();
-    // let max_x = scans
-    //     .iter()
-    //     .flat_map(|s| s.iter().map(|x| x.0).max())
-    //     .max()
-    //     .unwrap();
-    // let min_y = scans
-    //     .iter()
-    //     .flat_map(|s| s.iter().map(|x| x.1).min())
-    //     .min()
-    //     .unwrap();
+/// Renders columns `xs` of the cave with the path of the falling sand
+/// highlighted.
+fn render(grid: &Grid<Block>, xs: (i64, i64), max_y: i64, path: &[Point2]) -> Frame {
+    let mut frame = Frame::new((xs.1 - xs.0 + 1) as usize, (max_y + 1) as usize);
+    for y in 0..=max_y {
+        for x in xs.0..=xs.1 {
+            let (ch, fg) = match grid[Point2::xy(x, y)] {
+                Block::Air => ('.', Rgb(60, 60, 60)),
+                Block::Rock => ('#', Rgb::GRAY),
+                Block::Sand => ('o', Rgb::YELLOW),
+            };
+            frame.set(x - xs.0, y, ch, fg);
+        }
+    }
+    for p in path {
+        frame.highlight(p.x() - xs.0, p.y(), Rgb::BROWN);
+    }
+    frame
+}
+
+fn solve(mut scans: Vec<Vec<Point2>>, add_floor: bool, viz: &mut Viz) -> i64 {
     let max_y = scans
         .iter()
-        .flat_map(|s| s.iter().map(|x| x.1).max())
+        .flat_map(|s| s.iter().map(|p| p.y()).max())
         .max()
         .unwrap();
 
-    let source = (500, 0);
+    let source = Point2::xy(500, 0);
+    let xs = if add_floor {
+        (source.x() - max_y - 2, source.x() + max_y + 2)
+    } else {
+        let rock_xs = || scans.iter().flatten().map(|p| p.x());
+        (rock_xs().min().unwrap() - 1, rock_xs().max().unwrap() + 1)
+    };
     if add_floor {
         scans.push(vec![
-            (source.0 - 2 * max_y, 2 + max_y),
-            (source.0 + 2 * max_y, 2 + max_y),
+            Point2::xy(source.x() - 2 * max_y, 2 + max_y),
+            Point2::xy(source.x() + 2 * max_y, 2 + max_y),
         ]);
     }
 
-    let mut grid = Vec2D {
-        v: vec![Block::Air; ((2 + max_y + 2) * (source.0 + 2 * max_y + 1)) as usize],
-        stride: (source.0 + 2 * max_y + 1),
-    };
+    let mut grid = Grid::new(
+        (source.x() + 2 * max_y + 1) as usize,
+        (2 + max_y + 2) as usize,
+        Block::Air,
+    );
 
     for scan in scans {
         for p in scan.windows(2) {
-            if let &[(mut x1, mut y1), (mut x2, mut y2)] = p {
-                if y1 == y2 {
-                    if x2 < x1 {
-                        std::mem::swap(&mut x1, &mut x2);
-                    }
-
-                    for x in x1..=x2 {
-                        grid[(x, y1)] = Block::Rock;
-                    }
-                } else if x1 == x2 {
-                    if y2 < y1 {
-                        std::mem::swap(&mut y1, &mut y2);
-                    }
-
-                    for y in y1..=y2 {
-                        grid[(x1, y)] = Block::Rock;
-                    }
-                } else {
-                    panic!()
-                }
-            } else {
+            let (mut pos, to) = (p[0], p[1]);
+            if pos.x() != to.x() && pos.y() != to.y() {
                 panic!()
             }
+            let step = (to - pos).signum();
+            grid[pos] = Block::Rock;
+            while pos != to {
+                pos += step;
+                grid[pos] = Block::Rock;
+            }
         }
     }
 
@@ -102,54 +115,72 @@ fn solve(mut scans: Vec<Vec<(i64, i64)>>, add_floor: bool) -> i64 {
 
         loop {
             let mut any = false;
-            for dir in [0, -1, 1] {
-                if grid[(sand_pos.0 + dir, sand_pos.1 + 1)] == Block::Air {
-                    sand_pos.1 += 1;
-                    sand_pos.0 += dir;
+            for dir in [Point2::xy(0, 1), Point2::xy(-1, 1), Point2::xy(1, 1)] {
+                if grid[sand_pos + dir] == Block::Air {
+                    sand_pos += dir;
                     any = true;
                     break;
                 }
             }
             if !any {
                 grid[sand_pos] = Block::Sand;
+                viz.frame(|| {
+                    render(&grid, xs, max_y + 2, &collisions)
+                        .with_caption(format!("{} units of sand", distributed_sand + 1))
+                });
                 collisions.pop();
                 break;
             }
             collisions.push(sand_pos);
-            if sand_pos.1 > max_y + 2 {
+            if sand_pos.y() > max_y + 2 {
                 break 'outer;
             }
         }
         distributed_sand += 1;
     }
 
-    // for y in min_y..=max_y {
-    //     for x in min_x..=max_x {
-    //         print!(
-    //             "{}",
-    //             match grid[x as usize][y as usize] {
-    //                 Block::Air => '.',
-    //                 Block::Rock => '#',
-    //                 Block::Sand => 'o',
-    //             }
-    //         );
-    //     }
-    //     println!("");
-    // }
+    trace::event!("done", distributed_sand = distributed_sand);
+    trace::snapshot!("grid", render(&grid, xs, max_y + 2, &[]));
+    viz.show(|| {
+        render(&grid, xs, max_y + 2, &[]).with_caption(format!("{distributed_sand} units of sand"))
+    });
 
     distributed_sand
 }
 
 pub fn solve_a() -> Result<i64> {
-    let f = std::fs::read("inputs/day14a")?;
-    let scans = parse_scans(&f[..]).unwrap().1;
-    Ok(solve(scans, false))
+    let f = read_bytes("inputs/day14a")?;
+    let scans = parse(&f);
+    Ok(solve(scans, false, &mut Viz::off()))
 }
 
 pub fn solve_b() -> Result<i64> {
-    let f = std::fs::read("inputs/day14a")?;
-    let scans = parse_scans(&f[..]).unwrap().1;
-    Ok(solve(scans, true))
+    let f = read_bytes("inputs/day14a")?;
+    let scans = parse(&f);
+    Ok(solve(scans, true, &mut Viz::off()))
+}
+
+/// Falling sand, one frame per unit that comes to rest.
+pub struct Sand {
+    scans: Vec<Vec<Point2>>,
+    add_floor: bool,
+}
+
+impl Sand {
+    pub fn new(part_no: usize) -> Result<Self> {
+        let f = read_bytes("inputs/day14a")?;
+        Ok(Self {
+            scans: parse(&f),
+            add_floor: part_no == 2,
+        })
+    }
+}
+
+impl Visualize for Sand {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        solve(self.scans.clone(), self.add_floor, viz);
+        viz.finish()
+    }
 }
 
 #[cfg(test)]
@@ -1,88 +1,57 @@
-use lazy_static::lazy_static;
-use regex::bytes::Regex;
-use std::{collections::HashSet, io::BufRead, ops::RangeInclusive};
-
-lazy_static! {
-    static ref RE: Regex = Regex::new("=(-?[0-9]+)").unwrap();
-}
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::{collections::HashSet, io::BufRead};
 
+use crate::meta::{DayInfo, Status, Tag};
 use crate::{
     error::Result,
-    util::{l_infty, parse_num},
+    util::{ints::extract_ints, read_bytes, IntervalSet, Point2},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Beacon Exclusion Zone",
+    tags: &[Tag::Intervals],
+    status: Status::Solved,
 };
 
-fn parse_input() -> Result<Vec<[i64; 4]>> {
-    let f = std::fs::read("inputs/day15a")?;
-    Ok(f.lines()
+/// Returns each sensor with its closest beacon.
+pub fn parse(f: &[u8]) -> Vec<(Point2, Point2)> {
+    f.lines()
         .filter_map(|x| x.ok())
         .map(|s| {
-            let c = RE.find_iter(s.as_bytes());
             let mut v = [0i64; 4];
-            v.iter_mut().zip(c).for_each(|(x, m)| {
-                *x = parse_num(&m.as_bytes()[1..]).unwrap().1;
-            });
-            v
+            v.iter_mut()
+                .zip(extract_ints(s.as_bytes()))
+                .for_each(|(x, n)| *x = n);
+            (Point2::xy(v[0], v[1]), Point2::xy(v[2], v[3]))
         })
-        .collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+}
+
+fn parse_input() -> Result<Vec<(Point2, Point2)>> {
+    Ok(parse(&read_bytes("inputs/day15a")?))
 }
 
 pub fn solve_a() -> Result<i64> {
     let input = parse_input()?;
 
-    let mut sensor_map = HashSet::new();
+    let mut sensor_map = IntervalSet::new();
     let mut beacons = HashSet::new();
 
     let y = 2000000;
-    // let y = 10;
-    for sensor in input {
-        let [sx, sy, bx, by] = sensor;
-        if by == y {
-            beacons.insert(bx);
+    for (sensor, beacon) in input {
+        if beacon.y() == y {
+            beacons.insert(beacon.x());
         }
 
-        let d = l_infty((sx, sy), (bx, by));
-        let c = (sy - y).abs();
+        let d = sensor.manhattan(beacon);
+        let c = (sensor.y() - y).abs();
         let ceh = d - c;
 
-        // dbg!(sx + ceh - (sx - ceh));
-        for x in sx - ceh..=sx + ceh {
-            sensor_map.insert((x, y));
-        }
+        sensor_map.insert(sensor.x() - ceh..=sensor.x() + ceh);
     }
 
-    // for x in -4..=26 {
-    //     print!(
-    //         "{}",
-    //         if sensor_map.contains(&(x, y)) {
-    //             '#'
-    //         } else {
-    //             '.'
-    //         }
-    //     );
-    // }
-    // println!("");
-
-    Ok(sensor_map.len() as i64 - beacons.len() as i64)
-}
-
-trait Interval<Idx> {
-    fn merge(&self, other: &RangeInclusive<Idx>) -> Option<RangeInclusive<Idx>>;
-}
-
-impl Interval<i64> for RangeInclusive<i64> {
-    fn merge(&self, other: &RangeInclusive<i64>) -> Option<RangeInclusive<i64>> {
-        // if self and other overlap -> merge
-        if self.start() > self.end() || other.start() > other.end() {
-            return None;
-        }
-        if (*self.start() <= other.end() + 1i64 && self.start() >= other.start())
-            || (*self.end() + 1i64 >= *other.start() && self.start() <= other.start())
-        {
-            Some(*self.start().min(other.start())..=*self.end().max(other.end()))
-        } else {
-            None
-        }
-    }
+    Ok(sensor_map.total_len() - beacons.len() as i64)
 }
 
 pub fn solve_b() -> Result<i64> {
@@ -90,45 +59,72 @@ pub fn solve_b() -> Result<i64> {
 
     let n = 4000001;
 
-    let mut intervals: Vec<RangeInclusive<i64>> = vec![];
+    let mut covered = IntervalSet::new();
 
-    for y in 0..n as i64 {
-        intervals.clear();
-        for &sensor in &input {
-            let [sx, sy, bx, by] = sensor;
-            let d = l_infty((sx, sy), (bx, by));
-            let c = (sy - y).abs();
+    for y in 0..n {
+        covered.clear();
+        for &(sensor, beacon) in &input {
+            let sx = sensor.x();
+            let d = sensor.manhattan(beacon);
+            let c = (sensor.y() - y).abs();
             let ceh = d - c;
-            let mut int = (sx - ceh).max(0)..=(sx + ceh).min(n as i64 - 1);
-            if int.start() <= int.end() {
-                let mut i = 0;
-                while i < intervals.len() {
-                    if let Some(merged) = intervals[i].merge(&int) {
-                        intervals.swap_remove(i);
-                        int = merged;
-                    } else {
-                        i += 1;
-                    }
-                }
-                intervals.push(int);
+            // most sensors do not reach the row at all
+            if ceh < 0 {
+                continue;
             }
+            covered.insert((sx - ceh).max(0)..=(sx + ceh).min(n - 1));
         }
-        if intervals.len() != 1 {
-            let mut p = [
-                intervals[0].start(),
-                intervals[0].end(),
-                intervals[1].start(),
-                intervals[1].end(),
-            ];
-            p.sort();
-            let x = p[1] + 1;
-            return Ok(x as i64 * (n as i64 - 1) + y);
+        if !covered.contains_range(0..=n - 1) {
+            let x = *covered.gaps_in(0..=n - 1).next().unwrap().start();
+            return Ok(x * (n - 1) + y);
         }
     }
 
     panic!();
 }
 
+/// Generates `n` sensors for part 2. Eight large sensors cover the whole
+/// search area except for a single point in row 200000, the remaining sensors
+/// are small and placed randomly, so every row up to the distress beacon has
+/// to merge `n` intervals.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let target = Point2::xy(rng.gen_range(0..4000000i64), 200000);
+    let r = 4000000i64;
+    let mut sensors = vec![];
+    for (dx, dy, radius) in [
+        (r + 1, 0, r),
+        (-r - 1, 0, r),
+        (0, r + 1, r),
+        (0, -r - 1, r),
+        (r + 1, r + 1, 2 * r),
+        (r + 1, -r - 1, 2 * r),
+        (-r - 1, r + 1, 2 * r),
+        (-r - 1, -r - 1, 2 * r),
+    ] {
+        let sensor = target + Point2::xy(dx, dy);
+        sensors.push((sensor, sensor + Point2::xy(radius, 0)));
+    }
+    while sensors.len() < n {
+        let sensor = Point2::xy(rng.gen_range(0..4000000), rng.gen_range(0..4000000));
+        let beacon = sensor + Point2::xy(rng.gen_range(0..1000), rng.gen_range(0..1000));
+        if sensor.manhattan(target) > sensor.manhattan(beacon) {
+            sensors.push((sensor, beacon));
+        }
+    }
+    let mut s = String::new();
+    for (sensor, beacon) in sensors {
+        s += &format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+            sensor.x(),
+            sensor.y(),
+            beacon.x(),
+            beacon.y()
+        );
+    }
+    s.into_bytes()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -142,35 +138,4 @@ mod test {
     fn test_b() {
         assert_eq!(solve_b().unwrap(), 26686);
     }
-
-    #[test]
-    fn test_merge() {
-        assert_eq!((0..=3).merge(&(4..=5)), Some(0..=5));
-        assert_eq!((0..=4).merge(&(4..=5)), Some(0..=5));
-        assert_eq!((0..=5).merge(&(4..=5)), Some(0..=5));
-
-        assert_eq!((4..=5).merge(&(0..=3)), Some(0..=5));
-        assert_eq!((4..=5).merge(&(0..=4)), Some(0..=5));
-        assert_eq!((4..=5).merge(&(0..=5)), Some(0..=5));
-
-        assert_eq!((0..=3).merge(&(5..=6)), None);
-        assert_eq!((5..=6).merge(&(0..=3)), None);
-
-        assert_eq!((0..=6).merge(&(1..=3)), Some(0..=6));
-        assert_eq!((1..=3).merge(&(0..=6)), Some(0..=6));
-
-        assert_eq!((6..=0).merge(&(4..=5)), None);
-        assert_eq!((6..=0).merge(&(4..=5)), None);
-        assert_eq!((6..=0).merge(&(4..=5)), None);
-
-        assert_eq!((6..=0).merge(&(0..=3)), None);
-        assert_eq!((6..=0).merge(&(0..=4)), None);
-        assert_eq!((6..=0).merge(&(0..=5)), None);
-
-        assert_eq!((6..=0).merge(&(5..=6)), None);
-        assert_eq!((6..=0).merge(&(0..=3)), None);
-
-        assert_eq!((6..=0).merge(&(1..=3)), None);
-        assert_eq!((6..=0).merge(&(0..=6)), None);
-    }
 }
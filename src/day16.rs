@@ -1,86 +1,129 @@
-use std::collections::HashMap;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{
+        hash::FastMap,
+        memo::{Memo, Pack, Packer},
+        read_bytes,
+    },
+};
 
-use crate::error::Result;
+pub const INFO: DayInfo = DayInfo {
+    title: "Proboscidea Volcanium",
+    tags: &[Tag::GraphSearch, Tag::Dp],
+    status: Status::Solved,
+};
+
+#[derive(Clone, Copy, Debug)]
+struct State {
+    node: usize,
+    /// Width of `node` in the packed key, enough for every node of the graph.
+    node_bits: u32,
+    time_left: i64,
+    /// Bit `i` is set once the `i`-th valve with nonzero flow was opened.
+    opened: u64,
+    players: i64,
+}
+
+impl Pack for State {
+    fn pack(&self) -> u128 {
+        Packer::new()
+            .push(self.node, self.node_bits)
+            .push(self.time_left, 5)
+            .push(self.opened, 64)
+            .push(self.players, 2)
+            .finish()
+    }
+}
 
 fn solve(graph: Vec<Vec<usize>>, node_values: Vec<i64>, node: usize, players: i64) -> i64 {
-    let mut cache = HashMap::new();
-    solve_internal(
-        &mut cache,
-        &graph,
-        node,
+    let mut memo = Memo::new();
+    // only valves with nonzero flow are worth opening, so only they get a bit
+    let mut valves = 0;
+    let masks: Vec<u64> = node_values
+        .iter()
+        .map(|&v| {
+            if v == 0 {
+                return 0;
+            }
+            valves += 1;
+            1 << (valves - 1)
+        })
+        .collect();
+    let state = State {
         node,
-        if players > 0 { 26 } else { 30 },
-        node_values,
+        node_bits: usize::BITS - (graph.len() - 1).leading_zeros(),
+        time_left: if players > 0 { 26 } else { 30 },
+        opened: 0,
         players,
-    )
+    };
+    let res = solve_internal(&mut memo, &graph, node, &node_values, &masks, state);
+    trace::event!("solved", players = players, cache = memo.stats());
+    res
 }
 
 fn solve_internal(
-    cache: &mut HashMap<(usize, i64, Vec<i64>, i64), i64>,
+    memo: &mut Memo<u128, i64>,
     graph: &Vec<Vec<usize>>,
     initial_node: usize,
-    node: usize,
-    time_left: i64,
-    node_values: Vec<i64>,
-    players: i64,
+    node_values: &[i64],
+    masks: &[u64],
+    s: State,
 ) -> i64 {
-    if time_left == 0 {
-        return if players > 0 {
-            solve_internal(
-                cache,
-                graph,
-                initial_node,
-                initial_node,
-                26,
-                node_values,
-                players - 1,
-            )
+    if s.time_left == 0 {
+        return if s.players > 0 {
+            let next = State {
+                node: initial_node,
+                time_left: 26,
+                players: s.players - 1,
+                ..s
+            };
+            solve_internal(memo, graph, initial_node, node_values, masks, next)
         } else {
             0
         };
     }
-    if let Some(x) = cache.get(&(node, time_left, node_values.clone(), players)) {
-        *x
-    } else {
+    memo.get_packed(&s, |memo| {
         // substructure: visit neighbors with 1 less time, or open valve of current node
         let mut max_val = 0;
-        for neigh in &graph[node] {
+        for neigh in &graph[s.node] {
             // move to that node and solve subproblem
+            let next = State {
+                node: *neigh,
+                time_left: s.time_left - 1,
+                ..s
+            };
             max_val = max_val.max(solve_internal(
-                cache,
+                memo,
                 graph,
                 initial_node,
-                *neigh,
-                time_left - 1,
-                node_values.clone(),
-                players,
+                node_values,
+                masks,
+                next,
             ));
         }
         // open current node
-        if node_values[node] != 0 {
-            let mut node_values_prime = node_values.clone();
-            node_values_prime[node] = 0;
+        let mask = masks[s.node];
+        if mask != 0 && s.opened & mask == 0 {
+            let next = State {
+                time_left: s.time_left - 1,
+                opened: s.opened | mask,
+                ..s
+            };
             max_val = max_val.max(
-                solve_internal(
-                    cache,
-                    graph,
-                    initial_node,
-                    node,
-                    time_left - 1,
-                    node_values_prime,
-                    players,
-                ) + node_values[node] * (time_left - 1),
+                solve_internal(memo, graph, initial_node, node_values, masks, next)
+                    + node_values[s.node] * (s.time_left - 1),
             );
         }
-
-        cache.insert((node, time_left, node_values, players), max_val);
         max_val
-    }
+    })
 }
 
-fn parse_input() -> Result<(Vec<Vec<usize>>, Vec<i64>, HashMap<String, usize>)> {
-    let f = String::from_utf8(std::fs::read("inputs/day16a")?)?;
-    let mut name_map = HashMap::new();
+/// The tunnels from each valve, the flow rates and the valve names.
+pub fn parse(f: &[u8]) -> Result<(Vec<Vec<usize>>, Vec<i64>, FastMap<String, usize>)> {
+    let f = std::str::from_utf8(f)?;
+    let mut name_map = FastMap::default();
 
     let mut graph = Vec::new();
     let mut node_values = Vec::new();
@@ -105,6 +148,10 @@ fn parse_input() -> Result<(Vec<Vec<usize>>, Vec<i64>, HashMap<String, usize>)>
     Ok((graph, node_values, name_map))
 }
 
+fn parse_input() -> Result<(Vec<Vec<usize>>, Vec<i64>, FastMap<String, usize>)> {
+    parse(&read_bytes("inputs/day16a")?)
+}
+
 pub fn solve_a() -> Result<i64> {
     let (graph, node_values, name_map) = parse_input()?;
     let s = solve(graph, node_values, name_map[&"AA".to_string()], 0);
@@ -131,4 +178,16 @@ mod test {
     fn test_b() {
         assert_eq!(solve_b().unwrap(), 26686);
     }
+
+    #[test]
+    fn test_many_valves() {
+        // a ring of 100 valves where only the two next to the start have flow
+        let graph = (0..100)
+            .map(|i| vec![(i + 99) % 100, (i + 1) % 100])
+            .collect();
+        let mut node_values = vec![0; 100];
+        node_values[1] = 10;
+        node_values[99] = 20;
+        assert_eq!(solve(graph, node_values, 0, 0), 20 * 28 + 10 * 25);
+    }
 }
@@ -1,6 +1,18 @@
 use std::{collections::VecDeque, fmt::Debug};
 
-use crate::error::Result;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{cycle, read_bytes},
+    viz::{Frame, Rgb, Visualize, Viz},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Pyroclastic Flow",
+    tags: &[Tag::Simulation, Tag::CycleDetection],
+    status: Status::Solved,
+};
 
 enum Collision {
     None,
@@ -92,7 +104,56 @@ struct State {
     move_id: usize,
 }
 
+/// Renders the top `rows` rows of the chamber with the falling rock.
+fn render(map: &Map, rock: &[(i64, i64)], rock_pos: (i64, i64), rows: i64) -> Frame {
+    let top = (rock_pos.1 + 4).max(rows - 1);
+    let mut frame = Frame::new(9, rows as usize);
+    for r in 0..rows {
+        let y = top - r;
+        frame.set(0, r, '|', Rgb::GRAY);
+        frame.set(8, r, '|', Rgb::GRAY);
+        for x in 0..7 {
+            let (ch, fg) = if y < 0 {
+                ('-', Rgb::GRAY)
+            } else if y < map.height_offset as i64 {
+                ('=', Rgb::GRAY)
+            } else if map.get((x, y)) {
+                ('#', Rgb::BROWN)
+            } else {
+                ('.', Rgb(60, 60, 60))
+            };
+            frame.set(x + 1, r, ch, fg);
+        }
+    }
+    for e in rock {
+        frame.set(
+            e.0 + rock_pos.0 + 1,
+            top - e.1 - rock_pos.1,
+            '@',
+            Rgb::YELLOW,
+        );
+    }
+    frame
+}
+
+/// The jet pattern as `-1` for left and `1` for right.
+pub fn parse(input: &[u8]) -> Vec<i64> {
+    input
+        .trim_ascii()
+        .iter()
+        .map(|c| match c {
+            b'<' => -1,
+            b'>' => 1,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
 pub fn solve_a() -> Result<i64> {
+    tower(&mut Viz::off())
+}
+
+fn tower(viz: &mut Viz) -> Result<i64> {
     let mut map = Map::new();
 
     let rock_formations: [&[(i64, i64)]; 5] = [
@@ -103,22 +164,24 @@ pub fn solve_a() -> Result<i64> {
         &[(2, 0), (3, 0), (2, 1), (3, 1)],
     ];
 
-    let input = std::fs::read("inputs/day17a")?;
-    let mut movements = input.trim_ascii().iter().cycle();
+    let movements = parse(&read_bytes("inputs/day17a")?);
+    let mut movements = movements.iter().cycle();
 
     let mut highest_pos = -1i64;
 
     for (rock_num, rock) in rock_formations.iter().cycle().enumerate() {
         let mut rock_pos = (0, highest_pos + 4);
         loop {
+            viz.frame(|| {
+                render(&map, rock, rock_pos, 40).with_caption(format!(
+                    "rock {}, height {}",
+                    rock_num + 1,
+                    highest_pos + 1
+                ))
+            });
             // "spawn" rock
             // move instruction
-            let movement = movements.next().unwrap();
-            let m = match *movement as char {
-                '<' => -1,
-                '>' => 1,
-                _ => unreachable!(),
-            };
+            let m = *movements.next().unwrap();
             let move_intent = (rock_pos.0 + m, rock_pos.1);
             rock_pos = match check_collision(rock, &map, move_intent) {
                 Collision::None => move_intent,
@@ -144,17 +207,8 @@ pub fn solve_a() -> Result<i64> {
                 Collision::Wall => unreachable!(),
             };
         }
-        // println!("BOARD");
-        // for y in (0..=highest_pos).rev() {
-        //     for x in 0..7 {
-        //         if map.contains(&(x, y)) {
-        //             print!("#");
-        //         } else {
-        //             print!(".");
-        //         }
-        //     }
-        //     println!("");
-        // }
+        trace::event!("rock", rock_num = rock_num, highest_pos = highest_pos);
+        trace::snapshot!("board", format!("{:?}", map));
         if rock_num + 1 == 2022 {
             return Ok(highest_pos + 1);
         }
@@ -162,43 +216,6 @@ pub fn solve_a() -> Result<i64> {
     unreachable!()
 }
 
-#[derive(Debug)]
-pub struct CycleResult {
-    length: i64,
-    offset: i64,
-}
-
-pub fn floyd<St: Eq + Clone>(f: impl Fn(St) -> St, x0: St) -> CycleResult {
-    let mut tortoise = f(x0.clone());
-    let mut hare = f(f(x0.clone()));
-
-    while tortoise != hare {
-        tortoise = f(tortoise);
-        hare = f(f(hare));
-    }
-
-    let mut mu = 0;
-    tortoise = x0.clone();
-
-    while tortoise != hare {
-        tortoise = f(tortoise);
-        hare = f(hare);
-        mu += 1;
-    }
-
-    let mut lam = 1;
-    hare = f(tortoise.clone());
-    while tortoise != hare {
-        hare = f(hare);
-        lam += 1
-    }
-
-    CycleResult {
-        length: lam,
-        offset: mu,
-    }
-}
-
 pub fn solve_b() -> Result<i64> {
     let rock_formations: [&[(i64, i64)]; 5] = [
         &[(2i64, 0), (3, 0), (4, 0), (5, 0)],
@@ -208,10 +225,11 @@ pub fn solve_b() -> Result<i64> {
         &[(2, 0), (3, 0), (2, 1), (3, 1)],
     ];
 
-    let input = std::fs::read("inputs/day17a")?;
-    let movements = input.trim_ascii();
+    let movements = parse(&read_bytes("inputs/day17a")?);
 
-    let step_fn = |mut st: State| {
+    // one rock per step, yielding how much the tower grew
+    let step_fn = |st: &mut State| {
+        let height = st.m.height();
         let map = &mut st.m;
         let rock = rock_formations[st.rock_id];
         let mut rock_pos = (
@@ -223,11 +241,7 @@ pub fn solve_b() -> Result<i64> {
             }) as i64,
         );
         loop {
-            let m = match movements[st.move_id] as char {
-                '<' => -1,
-                '>' => 1,
-                _ => unreachable!(),
-            };
+            let m = movements[st.move_id];
             st.move_id += 1;
             st.move_id %= movements.len();
             let move_intent = (rock_pos.0 + m, rock_pos.1);
@@ -256,7 +270,7 @@ pub fn solve_b() -> Result<i64> {
         }
         st.rock_id += 1;
         st.rock_id %= rock_formations.len();
-        st
+        st.m.height() - height
     };
 
     let x0 = State {
@@ -264,39 +278,27 @@ pub fn solve_b() -> Result<i64> {
         rock_id: 0,
         move_id: 0,
     };
-    let cycle = floyd(step_fn, x0.clone());
+    let e = cycle::extrapolate(step_fn, x0, 1000000000000, |total, grown, times| {
+        *total += grown * times as i64
+    });
+    trace::event!("cycle", offset = e.cycle.offset, length = e.cycle.length);
+    Ok(e.total)
+}
 
-    let mut s = x0;
-    for _ in 0..cycle.offset {
-        s = step_fn(s);
-    }
+/// Falling rocks of part 1.
+pub struct Tower;
 
-    let mut s2 = s.clone();
-    for _ in 0..cycle.length {
-        s2 = step_fn(s2);
+impl Tower {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
     }
+}
 
-    assert_eq!(s, s2); // only the height_offset should change
-
-    let n = 1000000000000;
-    let skipped_cycles = (n - cycle.offset) / cycle.length;
-    let skipped_iters = skipped_cycles * cycle.length;
-
-    let height_difference = (s2.m.height() - s.m.height()) as i64;
-    let move_id_diff = s2.move_id as i64 - s.move_id as i64;
-
-    s.m.height_offset += (height_difference * skipped_cycles) as usize;
-    s.move_id = (s.move_id as i64 + move_id_diff * skipped_cycles)
-        .rem_euclid(movements.len() as i64) as usize;
-    s.rock_id = (s.rock_id + skipped_iters as usize) % rock_formations.len();
-
-    let new_iters = cycle.offset + skipped_cycles * cycle.length;
-
-    for _ in new_iters..n {
-        s = step_fn(s);
+impl Visualize for Tower {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        tower(viz)?;
+        viz.finish()
     }
-
-    Ok(s.m.height())
 }
 
 #[cfg(test)]
@@ -1,12 +1,51 @@
-use std::collections::{BTreeSet, HashSet};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{read_bytes, search, Point3},
+};
 
-use crate::error::Result;
-pub fn solve_a() -> Result<i64> {
-    let s = String::from_utf8(std::fs::read("inputs/day18a")?)?;
-    let mut map = [[[false; 22]; 22]; 22];
+pub const INFO: DayInfo = DayInfo {
+    title: "Boiling Boulders",
+    tags: &[Tag::Grid, Tag::GraphSearch],
+    status: Status::Solved,
+};
+
+type Map = [[[bool; 22]; 22]; 22];
+
+/// The cell at `p`, `None` outside of the map.
+fn get(map: &Map, p: Point3<i8>) -> Option<bool> {
+    map.get(p.x() as usize)
+        .and_then(|v| v.get(p.y() as usize))
+        .and_then(|v| v.get(p.z() as usize))
+        .copied()
+}
+
+/// The air cells of the map, explored without a goal to flood fill it.
+struct Air<'a>(&'a Map);
+
+impl search::Problem for Air<'_> {
+    type State = Point3<i8>;
+
+    fn neighbors(&mut self, n: &Point3<i8>, out: &mut Vec<(Point3<i8>, i64)>) {
+        out.extend(
+            n.adjacent()
+                .filter(|&p| get(self.0, p) == Some(false))
+                .map(|p| (p, 1)),
+        );
+    }
+
+    fn is_goal(&self, _: &Point3<i8>) -> bool {
+        false
+    }
+}
+
+/// The map of the droplet and the coordinates of its cubes.
+pub fn parse(input: &[u8]) -> Result<(Map, Vec<Point3<i8>>)> {
+    let s = std::str::from_utf8(input)?;
+    let mut map: Map = [[[false; 22]; 22]; 22];
     let mut coords = Vec::new();
     for l in s.lines() {
-        let mut v = [0i8; 3];
+        let mut v = Point3::<i8>::default();
         l.split(",")
             .filter_map(|x| x.parse().ok())
             .enumerate()
@@ -14,92 +53,38 @@ pub fn solve_a() -> Result<i64> {
                 v[i] = x;
             });
         coords.push(v);
-        map[v[0] as usize][v[1] as usize][v[2] as usize] = true;
+        map[v.x() as usize][v.y() as usize][v.z() as usize] = true;
     }
+    Ok((map, coords))
+}
+
+pub fn solve_a() -> Result<i64> {
+    let (map, coords) = parse(&read_bytes("inputs/day18a")?)?;
     let mut surface = 0;
     for c in coords {
-        for d in [-1, 1] {
-            for dir in [(1, 0, 0), (0, 1, 0), (0, 0, 1)] {
-                let dir = (dir.0 * d, dir.1 * d, dir.2 * d);
-                let p = (c[0] + dir.0, c[1] + dir.1, c[2] + dir.2);
-                if map
-                    .get(p.0 as usize)
-                    .and_then(|v| v.get(p.1 as usize))
-                    .and_then(|v| v.get(p.2 as usize))
-                    .map(|x| !x)
-                    .unwrap_or(true)
-                {
-                    surface += 1;
-                }
+        for p in c.adjacent() {
+            if get(&map, p).map(|x| !x).unwrap_or(true) {
+                surface += 1;
             }
         }
     }
     Ok(surface)
 }
 pub fn solve_b() -> Result<i64> {
-    let s = String::from_utf8(std::fs::read("inputs/day18a")?)?;
-    let mut map = [[[false; 22]; 22]; 22];
-    let mut reachable = map.clone();
-    let mut coords = Vec::new();
-    for l in s.lines() {
-        let mut v = [0i8; 3];
-        l.split(",")
-            .filter_map(|x| x.parse().ok())
-            .enumerate()
-            .for_each(|(i, x)| {
-                v[i] = x;
-            });
-        coords.push(v);
-        map[v[0] as usize][v[1] as usize][v[2] as usize] = true;
-    }
+    let (map, coords) = parse(&read_bytes("inputs/day18a")?)?;
+    let mut reachable: Map = [[[false; 22]; 22]; 22];
 
-    let mut q = BTreeSet::new();
-    let mut visited = HashSet::new();
-    q.insert((0, 0, 0));
     assert!(!map[0][0][0]);
-
-    while let Some(n) = q.pop_first() {
-        visited.insert(n);
-        reachable[n.0 as usize][n.1 as usize][n.2 as usize] = true;
-        for d in [-1i32, 1] {
-            for dir in [(1, 0, 0), (0, 1, 0), (0, 0, 1)] {
-                let dir = (dir.0 * d, dir.1 * d, dir.2 * d);
-                let p = (n.0 + dir.0, n.1 + dir.1, n.2 + dir.2);
-                if map
-                    .get(p.0 as usize)
-                    .and_then(|v| v.get(p.1 as usize))
-                    .and_then(|v| v.get(p.2 as usize))
-                    .map(|x| !x)
-                    .unwrap_or(false)
-                    && !visited.contains(&p)
-                {
-                    q.insert(p);
-                }
-            }
-        }
+    let air = search::bfs(&mut Air(&map), Point3::default());
+    for (n, _) in air.reached() {
+        reachable[n.x() as usize][n.y() as usize][n.z() as usize] = true;
     }
 
     let mut surface = 0;
     for c in coords {
-        for d in [-1, 1] {
-            for dir in [(1, 0, 0), (0, 1, 0), (0, 0, 1)] {
-                let dir = (dir.0 * d, dir.1 * d, dir.2 * d);
-                let p = (c[0] + dir.0, c[1] + dir.1, c[2] + dir.2);
-                if map
-                    .get(p.0 as usize)
-                    .and_then(|v| v.get(p.1 as usize))
-                    .and_then(|v| v.get(p.2 as usize))
-                    .map(|x| !x)
-                    .unwrap_or(true)
-                    && reachable
-                        .get(p.0 as usize)
-                        .and_then(|v| v.get(p.1 as usize))
-                        .and_then(|v| v.get(p.2 as usize))
-                        .map(|x| *x)
-                        .unwrap_or(true)
-                {
-                    surface += 1;
-                }
+        for p in c.adjacent() {
+            if get(&map, p).map(|x| !x).unwrap_or(true) && get(&reachable, p).unwrap_or(true) {
+                surface += 1;
             }
         }
     }
@@ -1,9 +1,20 @@
-use std::collections::HashMap;
-
-use crate::{error::Result, util::read_string};
-use lazy_static::lazy_static;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{
+        ints::extract_ints,
+        memo::{Memo, Pack, Packer},
+        read_bytes,
+    },
+};
 use rayon::prelude::*;
-use regex::Regex;
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Not Enough Minerals",
+    tags: &[Tag::GraphSearch, Tag::Dp],
+    status: Status::Solved,
+};
 
 // ore
 // clay
@@ -22,24 +33,18 @@ struct Cost([i64; 4]);
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Machines([i64; 4]);
 
-lazy_static! {
-    static ref RE: Regex = Regex::new(r"Blueprint \d+: Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.").unwrap();
-}
-
 // Blueprint 1: Each ore robot costs 3 ore. Each clay robot costs 4 ore. Each obsidian robot costs 4 ore and 13 clay. Each geode robot costs 3 ore and 7 obsidian.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Blueprint([Cost; 4]);
-
-fn parse_input() -> Result<Vec<Blueprint>> {
-    let i = read_string("inputs/day19a")?;
-    Ok(RE
-        .captures_iter(i.as_str())
-        .map(|c| {
-            let mut c = c
-                .iter()
-                .skip(1)
-                .flatten()
-                .filter_map(|x| x.as_str().parse::<i64>().ok());
+pub struct Blueprint([Cost; 4]);
+
+/// The blueprints, robot costs in the order ore, clay, obsidian, geode.
+pub fn parse(i: &[u8]) -> Result<Vec<Blueprint>> {
+    Ok(std::str::from_utf8(i)?
+        .lines()
+        .filter(|line| line.starts_with("Blueprint"))
+        .map(|line| {
+            // the first number is the id of the blueprint
+            let mut c = extract_ints(line.as_bytes()).skip(1);
             Blueprint([
                 Cost([c.next().unwrap(), 0, 0, 0]),
                 Cost([c.next().unwrap(), 0, 0, 0]),
@@ -50,15 +55,21 @@ fn parse_input() -> Result<Vec<Blueprint>> {
         .collect())
 }
 
+fn parse_input() -> Result<Vec<Blueprint>> {
+    parse(&read_bytes("inputs/day19a")?)
+}
+
 fn solve_bp(bp: &Blueprint, time_left: i64) -> i64 {
-    let mut cache = HashMap::new();
-    solve_internal(
+    let mut memo = Memo::new();
+    let res = solve_internal(
         bp,
-        &mut cache,
+        &mut memo,
         time_left,
         Materials([0, 0, 0, 0]),
         Machines([1, 0, 0, 0]),
-    )
+    );
+    trace::event!("blueprint", geodes = res, cache = memo.stats());
+    res
 }
 
 fn step(bp: &Blueprint, time_left: i64, mut materials: Materials, machines: Machines) -> Materials {
@@ -112,26 +123,26 @@ fn buy(
     }
 }
 
-fn compress_state(time_left: i64, materials: Materials, machines: Machines) -> i128 {
-    let mut i = 0i128;
-    let m = 256;
-    i += time_left as i128;
-    for x in materials.0 {
-        i *= m;
-        assert!(x < m as i64, "{:?} {:?}", materials, machines);
-        i += x as i128;
-    }
-    for x in machines.0 {
-        i *= m;
-        assert!(x < m as i64, "{:?} {:?}", machines, machines);
-        i += x as i128;
+#[derive(Clone, Copy, Debug)]
+struct State {
+    time_left: i64,
+    materials: Materials,
+    machines: Machines,
+}
+
+impl Pack for State {
+    fn pack(&self) -> u128 {
+        let mut p = Packer::new().push(self.time_left, 6);
+        for x in self.materials.0.into_iter().chain(self.machines.0) {
+            p = p.push(x, 8);
+        }
+        p.finish()
     }
-    i
 }
 
 fn solve_internal(
     bp: &Blueprint,
-    cache: &mut HashMap<i128, i64>,
+    memo: &mut Memo<u128, i64>,
     time_left: i64,
     materials: Materials,
     machines: Machines,
@@ -140,9 +151,12 @@ fn solve_internal(
         return value(materials);
     }
 
-    if let Some(x) = cache.get(&compress_state(time_left, materials, machines)) {
-        *x
-    } else {
+    let state = State {
+        time_left,
+        materials,
+        machines,
+    };
+    memo.get_packed(&state, |memo| {
         let mut best = 0;
 
         let mut can_buy = [false; 4];
@@ -154,7 +168,7 @@ fn solve_internal(
                 can_buy[machine_id] = true;
                 best = best.max(solve_internal(
                     bp,
-                    cache,
+                    memo,
                     time_left - 1,
                     step(bp, time_left, new_materials, machines),
                     new_machines,
@@ -170,16 +184,15 @@ fn solve_internal(
             // if we cannot buy all machines we might be able to buy in the future, we might want to do nothing
             best = best.max(solve_internal(
                 bp,
-                cache,
+                memo,
                 time_left - 1,
                 step(bp, time_left, materials, machines),
                 machines,
             ));
         }
 
-        cache.insert(compress_state(time_left, materials, machines), best);
         best
-    }
+    })
 }
 
 fn will_be_buyable(cost: Cost, machines: Machines) -> bool {
@@ -1,8 +1,25 @@
-use crate::{error::Result, util::read_string};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{error::Result, util::read_bytes};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Grove Positioning System",
+    tags: &[Tag::Simulation],
+    status: Status::Solved,
+};
+
+/// The encrypted numbers.
+pub fn parse(i: &[u8]) -> Result<Vec<i64>> {
+    Ok(std::str::from_utf8(i)?
+        .lines()
+        .map(|x| x.parse().unwrap())
+        .collect())
+}
 
 fn parse_input() -> Result<Vec<i64>> {
-    let i = read_string("inputs/day20a")?;
-    Ok(i.lines().map(|x| x.parse().unwrap()).collect())
+    parse(&read_bytes("inputs/day20a")?)
 }
 
 fn solve(m: i64, n: i64) -> Result<i64> {
@@ -48,6 +65,27 @@ pub fn solve_b() -> Result<i64> {
     solve(811589153, 10)
 }
 
+/// Generates `n` random numbers, exactly one of which is zero.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let zero = rng.gen_range(0..n);
+    let mut s = String::new();
+    for i in 0..n {
+        let x = if i == zero {
+            0
+        } else {
+            let x: i64 = rng.gen_range(1..10000);
+            if rng.gen() {
+                x
+            } else {
+                -x
+            }
+        };
+        s += &format!("{}\n", x);
+    }
+    s.into_bytes()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
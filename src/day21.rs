@@ -1,11 +1,25 @@
-use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 
-use crate::{error::Result, util::read_string};
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    trace,
+    util::{
+        hash::{FastMap, FastSet},
+        read_bytes,
+    },
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Monkey Math",
+    tags: &[Tag::Parsing],
+    status: Status::Solved,
+};
 
 type Name<'a> = &'a [u8];
 
 #[derive(Debug, PartialEq, Eq)]
-enum Op<'a> {
+pub enum Op<'a> {
     Const(i64),
     Add(Name<'a>, Name<'a>),
     Mul(Name<'a>, Name<'a>),
@@ -16,8 +30,8 @@ enum Op<'a> {
 }
 
 fn eval_op<'a>(
-    m: &HashMap<&'a [u8], Op<'a>>,
-    cache: &mut HashMap<&'a [u8], Option<i64>>,
+    m: &FastMap<&'a [u8], Op<'a>>,
+    cache: &mut FastMap<&'a [u8], Option<i64>>,
     name: &'a [u8],
 ) -> Option<i64> {
     if let Some(v) = cache.get(name) {
@@ -40,9 +54,10 @@ fn eval_op<'a>(
     }
 }
 
-pub fn solve_a() -> Result<i64> {
-    let s = read_string("inputs/day21a")?;
-    let mut map = HashMap::new();
+/// The job of every monkey by name.
+pub fn parse(input: &[u8]) -> Result<FastMap<Name<'_>, Op<'_>>> {
+    let s = std::str::from_utf8(input)?;
+    let mut map = FastMap::default();
     for l in s.lines() {
         let s = l.split(": ").collect::<Vec<_>>();
         let res = s[0].as_bytes();
@@ -57,7 +72,13 @@ pub fn solve_a() -> Result<i64> {
 
         map.insert(res, inp);
     }
-    let mut cache = HashMap::new();
+    Ok(map)
+}
+
+pub fn solve_a() -> Result<i64> {
+    let s = read_bytes("inputs/day21a")?;
+    let map = parse(&s)?;
+    let mut cache = FastMap::default();
     let v = eval_op(&map, &mut cache, b"root").unwrap();
     Ok(v)
 }
@@ -68,7 +89,7 @@ enum Direction {
     Right,
 }
 
-fn find_var(map: &HashMap<&[u8], Op>, k: &[u8]) -> Option<Direction> {
+fn find_var(map: &FastMap<&[u8], Op>, k: &[u8]) -> Option<Direction> {
     match map[k] {
         Op::Const(_) => None,
         Op::Add(a, b) | Op::Mul(a, b) | Op::Sub(a, b) | Op::Div(a, b) | Op::Eq(a, b) => {
@@ -80,8 +101,13 @@ fn find_var(map: &HashMap<&[u8], Op>, k: &[u8]) -> Option<Direction> {
     }
 }
 
-#[allow(unused)]
-fn print_eq<'a>(visited: &mut HashSet<&'a [u8]>, map: &HashMap<&'a [u8], Op<'a>>, k: &'a [u8]) {
+/// Writes the equations `k` depends on as an SMT-LIB model to `out`.
+fn write_eq<'a>(
+    out: &mut String,
+    visited: &mut FastSet<&'a [u8]>,
+    map: &FastMap<&'a [u8], Op<'a>>,
+    k: &'a [u8],
+) {
     if visited.contains(&k) {
         return;
     }
@@ -89,70 +115,60 @@ fn print_eq<'a>(visited: &mut HashSet<&'a [u8]>, map: &HashMap<&'a [u8], Op<'a>>
     let v = &map[k];
     let k = std::str::from_utf8(k).unwrap();
     match v {
-        Op::Const(i) => println!("(define-fun {k} () Int {i})"),
+        Op::Const(i) => writeln!(out, "(define-fun {k} () Int {i})").unwrap(),
         Op::Add(a, b) => {
-            print_eq(visited, map, a);
-            print_eq(visited, map, b);
+            write_eq(out, visited, map, a);
+            write_eq(out, visited, map, b);
             let a = std::str::from_utf8(a).unwrap();
             let b = std::str::from_utf8(b).unwrap();
-            println!("(define-fun {k} () Int (+ {a} {b}))");
+            writeln!(out, "(define-fun {k} () Int (+ {a} {b}))").unwrap();
         }
         Op::Sub(a, b) => {
-            print_eq(visited, map, a);
-            print_eq(visited, map, b);
+            write_eq(out, visited, map, a);
+            write_eq(out, visited, map, b);
             let a = std::str::from_utf8(a).unwrap();
             let b = std::str::from_utf8(b).unwrap();
-            println!("(define-fun {k} () Int (- {a} {b}))");
+            writeln!(out, "(define-fun {k} () Int (- {a} {b}))").unwrap();
         }
         Op::Mul(a, b) => {
-            print_eq(visited, map, a);
-            print_eq(visited, map, b);
+            write_eq(out, visited, map, a);
+            write_eq(out, visited, map, b);
             let a = std::str::from_utf8(a).unwrap();
             let b = std::str::from_utf8(b).unwrap();
-            println!("(define-fun {k} () Int (* {a} {b}))");
+            writeln!(out, "(define-fun {k} () Int (* {a} {b}))").unwrap();
         }
         Op::Div(a, b) => {
-            print_eq(visited, map, a);
-            print_eq(visited, map, b);
+            write_eq(out, visited, map, a);
+            write_eq(out, visited, map, b);
             let a = std::str::from_utf8(a).unwrap();
             let b = std::str::from_utf8(b).unwrap();
-            println!("(define-fun {k} () Int (div {a} {b}))");
+            writeln!(out, "(define-fun {k} () Int (div {a} {b}))").unwrap();
         }
         Op::Eq(a, b) => {
-            print_eq(visited, map, a);
-            print_eq(visited, map, b);
+            write_eq(out, visited, map, a);
+            write_eq(out, visited, map, b);
             let a = std::str::from_utf8(a).unwrap();
             let b = std::str::from_utf8(b).unwrap();
-            println!("(define-fun {k} () Bool (= {a} {b}))");
+            writeln!(out, "(define-fun {k} () Bool (= {a} {b}))").unwrap();
         }
         Op::Var => {
-            println!("(declare-const {k} Int)");
+            writeln!(out, "(declare-const {k} Int)").unwrap();
         }
     }
 }
 
 pub fn solve_b() -> Result<i64> {
-    let s = read_string("inputs/day21a")?;
-    let mut map = HashMap::new();
-    for l in s.lines() {
-        let s = l.split(": ").collect::<Vec<_>>();
-        let res = s[0].as_bytes();
-        let inp = match (res, s[1].split(" ").collect::<Vec<_>>().as_slice()) {
-            (b"root", [a, _, b]) => Op::Eq(a.as_bytes(), b.as_bytes()),
-            (b"humn", _) => Op::Var,
-            (_, [v]) => Op::Const(v.parse::<i64>().unwrap()),
-            (_, [a, "+", b]) => Op::Add(a.as_bytes(), b.as_bytes()),
-            (_, [a, "-", b]) => Op::Sub(a.as_bytes(), b.as_bytes()),
-            (_, [a, "*", b]) => Op::Mul(a.as_bytes(), b.as_bytes()),
-            (_, [a, "/", b]) => Op::Div(a.as_bytes(), b.as_bytes()),
-            _ => panic!(),
-        };
-
-        map.insert(res, inp);
-    }
+    let s = read_bytes("inputs/day21a")?;
+    let mut map = parse(&s)?;
+    let root = match map[b"root".as_slice()] {
+        Op::Add(a, b) | Op::Sub(a, b) | Op::Mul(a, b) | Op::Div(a, b) => Op::Eq(a, b),
+        _ => panic!(),
+    };
+    map.insert(b"root", root);
+    map.insert(b"humn", Op::Var);
 
     let names = map.keys().map(|x| Vec::from(*x)).collect::<Vec<_>>();
-    let mut cache = HashMap::new();
+    let mut cache = FastMap::default();
     // constant propagation
     for k in names.iter() {
         if let Some(v) = eval_op(&map, &mut cache, &k[..]) {
@@ -173,7 +189,7 @@ pub fn solve_b() -> Result<i64> {
                 Direction::Left => match map[left] {
                     Op::Const(_) => todo!(),
                     Op::Add(a, b) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             - eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -183,7 +199,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Mul(a, b) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             / eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -193,7 +209,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Sub(a, b) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             + eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -203,7 +219,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Div(a, b) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             * eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -218,7 +234,7 @@ pub fn solve_b() -> Result<i64> {
                 Direction::Right => match map[left] {
                     Op::Const(_) => todo!(),
                     Op::Add(b, a) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             - eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -228,7 +244,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Mul(b, a) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             / eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -238,7 +254,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Sub(b, a) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             + eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -248,7 +264,7 @@ pub fn solve_b() -> Result<i64> {
                         }
                     }
                     Op::Div(b, a) => {
-                        let mut cache = HashMap::new();
+                        let mut cache = FastMap::default();
                         let v = eval_op(&map, &mut cache, right).unwrap()
                             * eval_op(&map, &mut cache, b).unwrap();
                         map.entry(right).and_modify(|x| *x = Op::Const(v));
@@ -265,11 +281,11 @@ pub fn solve_b() -> Result<i64> {
         }
     }
 
-    // let mut visited = HashSet::new();
-    // print_eq(&mut visited, &map, b"root");
-    // println!("(assert (= root true))");
-    // println!("(check-sat)");
-    // println!("(get-model)");
+    trace::snapshot!("smt model", {
+        let mut smt = String::new();
+        write_eq(&mut smt, &mut FastSet::default(), &map, b"root");
+        smt + "(assert (= root true))\n(check-sat)\n(get-model)"
+    });
 
     Ok(match map[b"root".as_slice()] {
         Op::Eq(_, right) => match map[right] {
@@ -1,68 +1,75 @@
-use nom::{branch::alt, bytes::complete::tag, combinator::map, multi::many0, IResult};
-
-use crate::{error::Result, util::parse_num};
+use nom::{
+    branch::alt,
+    bytes::complete::take,
+    combinator::{map, map_opt},
+    multi::many0,
+    IResult,
+};
+
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{ints::int, read_bytes, Direction, Grid, Point2, Turn},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Monkey Map",
+    tags: &[Tag::Grid, Tag::Simulation, Tag::Parsing],
+    // only the cube's block size is found so far
+    status: Status::Stubbed(2),
+};
 
 #[derive(Debug)]
-enum Instruction {
+pub enum Instruction {
     Step(i64),
-    Left,
-    Right,
+    Turn(Turn),
 }
 
 fn parse_instructions(i: &[u8]) -> IResult<&[u8], Vec<Instruction>> {
     many0(alt((
-        map(parse_num, Instruction::Step),
-        map(tag(b"L"), |_| Instruction::Left),
-        map(tag(b"R"), |_| Instruction::Right),
+        map(int, Instruction::Step),
+        map_opt(take(1usize), |x: &[u8]| {
+            Turn::from_byte(x[0]).map(Instruction::Turn)
+        }),
     )))(i)
 }
 
-fn get_pos(map: &[&[u8]], p: [i32; 2]) -> Option<u8> {
-    p[1].try_into()
-        .ok()
-        .and_then(|y: usize| map.get(y))
-        .zip(TryInto::<usize>::try_into(p[0]).ok())
-        .and_then(|(xs, x)| xs.get(x).map(|x| *x))
+fn get_pos(map: &Grid<u8>, p: Point2) -> Option<u8> {
+    map.get(p).copied()
 }
 
-pub fn solve_a() -> Result<i64> {
-    let s = std::fs::read("inputs/day22a")?;
-    let mut map = Vec::new();
+/// Splits the input into the board, padded with spaces, and the path.
+pub fn parse(s: &[u8]) -> (Grid<u8>, Vec<Instruction>) {
+    let split = s.windows(2).position(|x| x == b"\n\n").unwrap_or(s.len());
+    let path = s.get(split + 2..).unwrap_or_default();
+    let instructions = parse_instructions(path.trim_ascii()).unwrap().1;
+    (Grid::parse(&s[..split], b' '), instructions)
+}
 
-    let mut lines = s.split(|x| *x == '\n' as u8);
-    for l in &mut lines {
-        if l.len() == 0 {
-            break;
-        }
-        map.push(l);
-    }
-    let mut p = [
-        map[0].iter().position(|x| *x == '.' as u8).unwrap() as i32,
+pub fn solve_a() -> Result<i64> {
+    let s = read_bytes("inputs/day22a")?;
+    let (map, instructions) = parse(&s);
+    let mut p = Point2::xy(
+        map.row(0).iter().position(|x| *x == '.' as u8).unwrap() as i64,
         0,
-    ];
-    let mut dir = 0i32;
-    let dirs = [[1, 0], [0, 1], [-1, 0], [0, -1]];
-    let instructions = parse_instructions(lines.next().unwrap()).unwrap().1;
-
+    );
+    let (w, h) = map.dims();
+    let mut dir = Direction::Right;
     for i in instructions.iter() {
         match i {
             Instruction::Step(n) => {
-                let d = dirs[dir as usize];
+                let d = dir.offset();
                 for _ in 0..*n {
-                    let new_p = [
-                        (p[0] + d[0]).rem_euclid(map[0].len() as i32),
-                        (p[1] + d[1]).rem_euclid(map.len() as i32),
-                    ];
+                    let new_p = p + d;
+                    let new_p = Point2::xy(new_p.x().rem_euclid(w), new_p.y().rem_euclid(h));
                     let b = get_pos(&map, new_p);
                     if b.unwrap_or(' ' as u8) == '.' as u8 {
                         p = new_p;
                     } else if b.unwrap_or(' ' as u8) == ' ' as u8 {
                         let mut new_p = new_p;
                         while ' ' as u8 == get_pos(&map, new_p).unwrap_or(' ' as u8) {
-                            new_p = [
-                                (new_p[0] + d[0]).rem_euclid(map[0].len() as i32),
-                                (new_p[1] + d[1]).rem_euclid(map.len() as i32),
-                            ];
+                            new_p += d;
+                            new_p = Point2::xy(new_p.x().rem_euclid(w), new_p.y().rem_euclid(h));
                         }
                         let v = get_pos(&map, new_p).unwrap();
                         if v == '.' as u8 {
@@ -73,37 +80,16 @@ pub fn solve_a() -> Result<i64> {
                     }
                 }
             }
-            Instruction::Left => {
-                dir -= 1;
-                dir = dir.rem_euclid(dirs.len() as i32);
-            }
-            Instruction::Right => {
-                dir += 1;
-                dir = dir.rem_euclid(dirs.len() as i32);
-            }
+            Instruction::Turn(t) => dir = dir.turn(*t),
         }
     }
 
-    Ok((1000 * (p[1] + 1) + 4 * (p[0] + 1) + dir) as i64)
+    Ok(1000 * (p.y() + 1) + 4 * (p.x() + 1) + dir.facing())
 }
 
 pub fn solve_b() -> Result<i64> {
-    let s = std::fs::read("inputs/day22x")?;
-    let mut map = Vec::new();
-
-    let mut lines = s.split(|x| *x == '\n' as u8);
-    for l in &mut lines {
-        map.push(Vec::from(l));
-        if l.len() == 0 {
-            break;
-        }
-    }
-
-    let max_len = map.iter().map(|x| x.len()).max().unwrap();
-
-    for l in &mut map {
-        l.extend(vec![' ' as u8; max_len - l.len()]);
-    }
+    let s = read_bytes("inputs/day22a")?;
+    let (map, _) = parse(&s);
 
     // let mut p = [
     //     map[0].iter().position(|x| *x == '.' as u8).unwrap() as i32,
@@ -112,7 +98,7 @@ pub fn solve_b() -> Result<i64> {
 
     // extract faces
     let mut block_size = map
-        .iter()
+        .rows()
         .filter_map(|x| {
             x.split(|x| *x != ' ' as u8)
                 .map(|x| x.len())
@@ -121,10 +107,10 @@ pub fn solve_b() -> Result<i64> {
         })
         .min()
         .unwrap();
-    for y in 0..map.len() {
+    for row in map.rows() {
         let mut run_len = 0;
-        for x in 0..map[y].len() {
-            if map[y][x] == ' ' as u8 {
+        for &x in row {
+            if x == ' ' as u8 {
                 run_len += 1;
             } else {
                 if run_len > 0 {
@@ -1,91 +1,104 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::VecDeque;
 
-use crate::error::Result;
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{
+    error::Result,
+    util::{hash::FastMap, read_bytes, Direction, Direction8, Grid, Point2, SparseGrid},
+    viz::{Frame, Rgb, Visualize, Viz},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Unstable Diffusion",
+    tags: &[Tag::Grid, Tag::Simulation],
+    status: Status::Solved,
+};
+
+/// The positions of the elves.
+pub fn parse(input: &[u8]) -> SparseGrid<()> {
+    let map = Grid::parse(input, b'.');
+    SparseGrid::from_grid(&map, |&c| c == b'#').map(|_| ())
+}
 
 pub fn solve_a() -> Result<i64> {
-    solve(false)
+    solve(false, &mut Viz::off())
+}
+
+/// Renders the bounding box of the elves, highlighting the ones that just
+/// moved.
+fn render(elves: &SparseGrid<()>, moved: &[Point2]) -> Frame {
+    let (min, _) = elves.bounds().unwrap();
+    let (w, h) = elves.dims();
+    let mut frame = Frame::new(w as usize, h as usize);
+    for y in 0..h {
+        for x in 0..w {
+            frame.set(x, y, '.', Rgb(60, 60, 60));
+        }
+    }
+    for elf in elves.positions() {
+        frame.set(elf.x() - min.x(), elf.y() - min.y(), '#', Rgb::GREEN);
+    }
+    for elf in moved {
+        frame.highlight(elf.x() - min.x(), elf.y() - min.y(), Rgb(0, 70, 0));
+    }
+    frame
 }
 
-fn solve(mode: bool) -> Result<i64> {
-    let s = std::fs::read("inputs/day23a")?;
+fn solve(mode: bool, viz: &mut Viz) -> Result<i64> {
+    let s = read_bytes("inputs/day23a")?;
     //     let s = b".....
     // ..##.
     // ..#..
     // .....
     // ..##.
     // .....";
-    let mut map = s
-        .split(|x| *x == '\n' as u8)
-        .map(Vec::from)
-        .collect::<Vec<_>>();
-    map.iter_mut().for_each(|line| {
-        line.iter_mut().for_each(|v| *v = (*v == '#' as u8) as u8);
-    });
-
-    let mut hashgrid = HashSet::new();
-    for y in 0..map.len() {
-        for x in 0..map[0].len() {
-            if map[y][x] == 1 {
-                hashgrid.insert([x as isize, y as isize]);
-            }
-        }
-    }
+    let mut elves = parse(&s);
 
     let mut directions = VecDeque::from([
-        [[0, -1], [-1, -1], [1, -1]],
-        [[0, 1], [-1, 1], [1, 1]],
-        [[-1, 0], [-1, -1], [-1, 1]],
-        [[1, 0], [1, -1], [1, 1]],
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
     ]);
 
     for round in if mode { 0..i64::MAX } else { 0..10 } {
-        let mut proposals = HashMap::new();
-        for elf in hashgrid.iter() {
-            let mut any_in_vicinity = false;
-            for dx in [-1, 0, 1] {
-                for dy in [-1, 0, 1] {
-                    if dx != 0 || dy != 0 {
-                        if hashgrid.contains(&[elf[0] + dx, elf[1] + dy]) {
-                            any_in_vicinity = true;
-                        }
-                    }
-                }
-            }
-            if !any_in_vicinity {
+        let mut proposals = FastMap::default();
+        for elf in elves.positions() {
+            if elves.count_neighbors8(elf) == 0 {
                 continue;
             }
 
-            for direction in directions.iter() {
-                let mut invalid = false;
-                for offset_to_check in direction {
-                    let new_pos = [elf[0] + offset_to_check[0], elf[1] + offset_to_check[1]];
-                    if hashgrid.contains(&new_pos) {
-                        invalid = true;
-                        break;
-                    }
-                }
+            for &direction in directions.iter() {
+                // the direction itself and the two diagonals next to it
+                let ahead = Direction8::from(direction);
+                let invalid = [ahead, ahead.turn_left(), ahead.turn_right()]
+                    .iter()
+                    .any(|d| elves.contains(elf + d.offset()));
                 if !invalid {
-                    let new_pos = [elf[0] + direction[0][0], elf[1] + direction[0][1]];
-                    proposals.insert(*elf, new_pos);
+                    proposals.insert(elf, elf + direction.offset());
                     break;
                 }
             }
         }
 
-        let mut proposal_counts = HashMap::<_, i64>::new();
+        let mut proposal_counts = FastMap::<_, i64>::default();
 
         for (_, prop) in proposals.iter() {
             *proposal_counts.entry(*prop).or_default() += 1;
         }
 
+        let shown = viz.tick();
+        let mut moved = Vec::new();
         let mut any_moved = false;
-        let positions: Vec<[isize; 2]> = hashgrid.iter().map(|x| *x).collect::<Vec<_>>();
+        let positions: Vec<Point2> = elves.positions().collect();
         for pos in positions {
             if let Some(p) = proposals.get(&pos) {
                 if proposal_counts[p] == 1 {
                     any_moved = true;
-                    hashgrid.remove(&pos);
-                    hashgrid.insert(*p);
+                    elves.remove(pos);
+                    elves.insert(*p, ());
+                    if shown {
+                        moved.push(*p);
+                    }
                 }
             }
         }
@@ -93,21 +106,43 @@ fn solve(mode: bool) -> Result<i64> {
         let f = directions.pop_front().unwrap();
         directions.push_back(f);
 
+        if shown {
+            viz.show(|| render(&elves, &moved).with_caption(format!("round {}", round + 1)));
+        }
+
         if mode && !any_moved {
+            viz.show(|| {
+                render(&elves, &[]).with_caption(format!("no moves in round {}", round + 1))
+            });
             return Ok(round + 1);
         }
     }
+    viz.show(|| render(&elves, &[]).with_caption("after 10 rounds"));
 
-    let min_x = hashgrid.iter().map(|p| p[0]).min().unwrap();
-    let max_x = hashgrid.iter().map(|p| p[0]).max().unwrap();
-    let min_y = hashgrid.iter().map(|p| p[1]).min().unwrap();
-    let max_y = hashgrid.iter().map(|p| p[1]).max().unwrap();
-    let r = (max_x - min_x + 1) * (max_y - min_y + 1);
-    Ok(r as i64 - hashgrid.len() as i64)
+    let (w, h) = elves.dims();
+    Ok(w * h - elves.len() as i64)
 }
 
 pub fn solve_b() -> Result<i64> {
-    solve(true)
+    solve(true, &mut Viz::off())
+}
+
+/// The elves spreading out, one frame per round.
+pub struct Elves {
+    mode: bool,
+}
+
+impl Elves {
+    pub fn new(part_no: usize) -> Result<Self> {
+        Ok(Self { mode: part_no == 2 })
+    }
+}
+
+impl Visualize for Elves {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        solve(self.mode, viz)?;
+        viz.finish()
+    }
 }
 
 #[cfg(test)]
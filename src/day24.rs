@@ -1,175 +1,222 @@
-use std::{
-    collections::{BTreeSet, HashMap},
-    ops::Sub,
-};
-
-use num::Signed;
-
+use crate::meta::{DayInfo, Status, Tag};
 use crate::{
     error::Result,
-    util::{gcd, Vec2D},
+    trace,
+    util::{
+        num::lcm,
+        read_bytes,
+        search::{self, Explored},
+        Direction, Grid, Point2,
+    },
+    viz::{Frame, Rgb, Visualize, Viz},
+};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Blizzard Basin",
+    tags: &[Tag::Grid, Tag::GraphSearch, Tag::CycleDetection],
+    status: Status::Solved,
 };
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
 struct State {
-    priority: i64,
     time: i64,
-    pos: [i64; 2],
+    pos: Point2,
 }
 
-impl State {
-    fn new(target: [i64; 2], pos: [i64; 2], time: i64) -> Self {
-        Self {
-            priority: l_infty(pos, target) + time,
-            pos: pos,
-            time: time,
+/// One walk through the valley from `source` to `target`.
+struct Leg<'a> {
+    problem: &'a Problem,
+    source: Point2,
+    target: Point2,
+    viz: &'a mut Viz,
+}
+
+impl search::Problem for Leg<'_> {
+    type State = State;
+
+    fn neighbors(&mut self, s: &State, out: &mut Vec<(State, i64)>) {
+        let (xs, ys) = self.problem.maps[0].dims();
+        let map = self.problem.map(s.time + 1);
+        let moves = Direction::ALL.map(|d| s.pos + d.offset());
+        for pos in [s.pos].into_iter().chain(moves) {
+            let inside = pos.x() > 0 && pos.y() > 0 && pos.x() < xs - 1 && pos.y() < ys - 1;
+            if (inside || pos == self.source || pos == self.target) && !map[pos] {
+                let time = s.time + 1;
+                out.push((State { time, pos }, 1));
+            }
         }
     }
-}
 
-pub fn l_infty<T: Copy + Signed + Sub<T> + std::iter::Sum, const N: usize>(
-    a: [T; N],
-    b: [T; N],
-) -> T {
-    a.iter()
-        .zip(b.iter())
-        .map(|(x, y)| (*x - *y).abs())
-        .sum::<T>()
+    fn is_goal(&self, s: &State) -> bool {
+        s.pos == self.target
+    }
+
+    fn heuristic(&self, s: &State) -> i64 {
+        s.pos.manhattan(self.target)
+    }
+
+    fn visit(
+        &mut self,
+        s: &State,
+        _explored: &Explored<State>,
+        frontier: &mut dyn Iterator<Item = &State>,
+    ) {
+        self.viz.frame(|| {
+            let frontier: Vec<_> = frontier
+                .filter(|x| x.time == s.time)
+                .map(|x| x.pos)
+                .collect();
+            render_map(self.problem.map(s.time), s.pos, &frontier).with_caption(format!(
+                "minute {}, {} states in the frontier",
+                s.time,
+                frontier.len()
+            ))
+        });
+    }
 }
 
-#[allow(unused)]
-fn print_map(map: &Vec2D<bool>, pos: [i64; 2]) {
+/// Renders the valley with the blizzards at one point in time, the
+/// expedition at `pos` and `frontier` highlighted.
+fn render_map(map: &Grid<bool>, pos: Point2, frontier: &[Point2]) -> Frame {
     let (xs, ys) = map.dims();
+    let mut frame = Frame::new(xs as usize, ys as usize);
     for y in 0..ys {
         for x in 0..xs {
-            if map[(x, y)] {
-                print!("x");
-            } else if pos == [x, y] {
-                print!("E");
+            if map[Point2::xy(x, y)] {
+                frame.set(x, y, '*', Rgb::CYAN);
+            } else if x == 0 || y == 0 || x == xs - 1 || y == ys - 1 {
+                frame.set(x, y, '#', Rgb::GRAY);
             } else {
-                print!(".");
+                frame.set(x, y, '.', Rgb(60, 60, 60));
             }
         }
-        println!("");
     }
+    frame.set(1, 0, '.', Rgb(60, 60, 60));
+    frame.set(xs - 2, ys - 1, '.', Rgb(60, 60, 60));
+    for p in frontier {
+        frame.highlight(p.x(), p.y(), Rgb(0, 70, 0));
+    }
+    frame.set(pos.x(), pos.y(), 'E', Rgb::WHITE);
+    frame.highlight(pos.x(), pos.y(), Rgb::RED);
+    frame
 }
 
 struct Problem {
-    maps: Vec<Vec2D<bool>>,
+    maps: Vec<Grid<bool>>,
+}
+
+/// The size of the valley including its walls, and the position and
+/// direction of every blizzard.
+pub fn parse(input: &[u8]) -> ((usize, usize), Vec<(Point2, Point2)>) {
+    let map = Grid::parse(input, b'#');
+    let blizzards = map
+        .iter()
+        .filter_map(|(p, v)| {
+            let dir = Direction::from_byte(*v)?;
+            Some((p, dir.offset()))
+        })
+        .collect::<Vec<_>>();
+    ((map.width(), map.height()), blizzards)
 }
 
 impl Problem {
     fn new() -> Result<Self> {
-        let s = std::fs::read("inputs/day24a")?;
-        let map = s
-            .split(|x| *x == '\n' as u8)
-            .map(Vec::from)
-            .collect::<Vec<_>>();
-
-        let blizzards = map
-            .iter()
-            .enumerate()
-            .flat_map(|(y, l)| {
-                l.iter().enumerate().filter_map(move |(x, v)| {
-                    let dir = match *v as char {
-                        'v' => [0i64, 1],
-                        '^' => [0, -1],
-                        '>' => [1, 0],
-                        '<' => [-1, 0],
-                        _ => return None,
-                    };
-                    Some(([x as i64, y as i64], dir))
-                })
-            })
-            .collect::<Vec<_>>();
-
-        let xs = map[0].len();
-        let ys = map.len();
-        let cycle_len = ((xs - 2) * (ys - 2)) as i64 / gcd((xs - 2) as _, (ys - 2) as _) as i64;
+        let ((xs, ys), blizzards) = parse(&read_bytes("inputs/day24a")?);
+        let cycle_len = lcm(xs - 2, ys - 2) as i64;
         let maps = (0..cycle_len)
             .map(|t| {
                 let t = t as i64;
-                let mut map = Vec2D {
-                    v: vec![false; xs * ys],
-                    stride: xs as _,
-                };
+                let mut map = Grid::new(xs, ys, false);
                 for (bpos, dir) in blizzards.iter() {
-                    let mut new_bpos = [bpos[0] + dir[0] * t, bpos[1] + dir[1] * t];
+                    let mut new_bpos = *bpos + *dir * t;
                     new_bpos[0] = ((new_bpos[0] - 1).rem_euclid(xs as i64 - 2)) + 1;
                     new_bpos[1] = ((new_bpos[1] - 1).rem_euclid(ys as i64 - 2)) + 1;
-                    map[(new_bpos[0], new_bpos[1])] = true;
+                    map[new_bpos] = true;
                 }
-                // print_map(&map, target);
-                // println!("");
                 map
             })
             .collect::<Vec<_>>();
         Ok(Self { maps })
     }
 
-    fn source(&self) -> [i64; 2] {
-        [1, 0]
+    fn source(&self) -> Point2 {
+        Point2::xy(1, 0)
     }
 
-    fn target(&self) -> [i64; 2] {
+    fn target(&self) -> Point2 {
         let (xs, ys) = self.maps[0].dims();
-        [xs - 2, ys - 1]
+        Point2::xy(xs - 2, ys - 1)
     }
 
-    fn solve(&self, time: i64, source: [i64; 2], target: [i64; 2]) -> Option<i64> {
-        let mut frontier = BTreeSet::<State>::new();
-        frontier.insert(State::new(target, source, time));
-
-        let mut backlinks = HashMap::new();
-        let (xs, ys) = self.maps[0].dims();
+    /// The blizzards at `time`.
+    fn map(&self, time: i64) -> &Grid<bool> {
+        &self.maps[time as usize % self.maps.len()]
+    }
 
-        while let Some(s) = frontier.pop_first() {
-            if s.pos == target {
-                let t = s.time;
-                // let mut s = s;
-                // let mut path = Vec::new();
-                // while let Some(x) = backlinks.get(&s) {
-                //     path.push(*x);
-                //     s = *x;
-                // }
-                // path.reverse();
-                // for s in path.iter() {
-                // let map = &self.maps[s.time as usize % self.maps.len()];
-                // print_map(map, s.pos);
-                // println!("");
-                // }
-                return Some(t);
-            }
-            let map = &self.maps[(s.time + 1) as usize % self.maps.len()];
-
-            for dir in [[0, 0], [-1, 0], [1, 0], [0, -1], [0, 1]] {
-                let pos = [s.pos[0] + dir[0], s.pos[1] + dir[1]];
-                if (pos[0] > 0 && pos[1] > 0 && pos[0] < xs as i64 - 1 && pos[1] < ys as i64 - 1)
-                    || pos == source
-                    || pos == target
-                {
-                    if !map[(pos[0], pos[1])] {
-                        let s_prime = State::new(target, pos, s.time + 1);
-                        backlinks.entry(s_prime).and_modify(|x| *x = s).or_insert(s);
-                        frontier.insert(s_prime);
-                    }
-                }
+    fn solve(&self, time: i64, source: Point2, target: Point2, viz: &mut Viz) -> Option<i64> {
+        let mut leg = Leg {
+            problem: self,
+            source,
+            target,
+            viz,
+        };
+        let _phase = trace::phase!("search");
+        let explored = search::astar(&mut leg, State { time, pos: source });
+        let arrival = explored.goal()?.time;
+        trace::event!("found", time = arrival, stats = explored.stats);
+        if trace::enabled(module_path!()) || leg.viz.is_on() {
+            for s in explored.path()? {
+                let map = self.map(s.time);
+                trace::snapshot!(&format!("t={}", s.time), render_map(map, s.pos, &[]));
+                leg.viz.show(|| {
+                    render_map(map, s.pos, &[]).with_caption(format!("minute {}", s.time))
+                });
             }
         }
-        None
+        Some(arrival)
+    }
+
+    /// The trips through the valley, to the target and for part 2 back and
+    /// forth again. Returns the time of arrival.
+    fn trips(&self, part_no: usize, viz: &mut Viz) -> Option<i64> {
+        let t = self.solve(0, self.source(), self.target(), viz)?;
+        if part_no == 1 {
+            return Some(t);
+        }
+        let t = self.solve(t, self.target(), self.source(), viz)?;
+        self.solve(t, self.source(), self.target(), viz)
     }
 }
 
 pub fn solve_a() -> Result<i64> {
     let p = Problem::new()?;
-    Ok(p.solve(0, p.source(), p.target()).unwrap())
+    Ok(p.trips(1, &mut Viz::off()).unwrap())
 }
 pub fn solve_b() -> Result<i64> {
     let p = Problem::new()?;
-    let t = p.solve(0, p.source(), p.target()).unwrap();
-    let t = p.solve(t, p.target(), p.source()).unwrap();
-    let t = p.solve(t, p.source(), p.target()).unwrap();
-    Ok(t)
+    Ok(p.trips(2, &mut Viz::off()).unwrap())
+}
+
+/// The blizzard search followed by a replay of the path that was found.
+pub struct Expedition {
+    problem: Problem,
+    part_no: usize,
+}
+
+impl Expedition {
+    pub fn new(part_no: usize) -> Result<Self> {
+        Ok(Self {
+            problem: Problem::new()?,
+            part_no,
+        })
+    }
+}
+
+impl Visualize for Expedition {
+    fn visualize(&self, viz: &mut Viz) -> Result<()> {
+        self.problem.trips(self.part_no, viz);
+        viz.finish()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,18 @@
 use std::io::BufRead;
 
-use crate::error::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-fn parse_num(n: &str) -> i64 {
+use crate::meta::{DayInfo, Status, Tag};
+use crate::{error::Result, util::read_bytes};
+
+pub const INFO: DayInfo = DayInfo {
+    title: "Full of Hot Air",
+    tags: &[Tag::Parsing],
+    status: Status::Solved,
+};
+
+fn parse_snafu(n: &str) -> i64 {
     n.chars().fold(0, |a, x| {
         let v = match x {
             '=' => -2,
@@ -53,15 +63,29 @@ fn fmt_base(mut n: i64) -> String {
         .collect()
 }
 
+/// The fuel requirements in decimal.
+pub fn parse(f: &[u8]) -> Vec<i64> {
+    f.lines()
+        .filter_map(|x| x.ok().map(|x| parse_snafu(x.as_str())))
+        .collect()
+}
+
 pub fn solve_a() -> Result<String> {
-    let f = std::fs::read("inputs/day25a")?;
-    let s = f
-        .lines()
-        .filter_map(|x| x.ok().map(|x| parse_num(x.as_str())))
-        .sum();
+    let s = parse(&read_bytes("inputs/day25a")?).iter().sum();
     Ok(fmt_base(s))
 }
 
+/// Generates `n` random SNAFU numbers.
+pub fn gen_input(n: usize) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut s = String::new();
+    for _ in 0..n {
+        s += &fmt_base(rng.gen_range(1..1_000_000_000_000));
+        s += "\n";
+    }
+    s.into_bytes()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -70,7 +94,7 @@ mod test {
         // assert_eq!(solve_a().unwrap(), 3877);
 
         for x in 0..100000 {
-            assert_eq!(x, parse_num(dbg!(fmt_base(dbg!(x))).as_str()));
+            assert_eq!(x, parse_snafu(dbg!(fmt_base(dbg!(x))).as_str()));
         }
     }
     #[test]
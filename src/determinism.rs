@@ -0,0 +1,133 @@
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::error::Result;
+use crate::util::{hash, take_opt};
+use crate::Solutions;
+
+/// Rayon pool size and hash seed of one run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Config {
+    threads: usize,
+    hash_seed: u64,
+}
+
+impl Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} threads, hash seed {}", self.threads, self.hash_seed)
+    }
+}
+
+/// `runs` configurations alternating between 1 and `threads` threads, each
+/// pool size once per hash seed.
+fn configs(runs: usize, threads: usize) -> Vec<Config> {
+    (0..runs)
+        .map(|k| Config {
+            threads: if k % 2 == 0 { 1 } else { threads },
+            hash_seed: (k / 2) as u64,
+        })
+        .collect()
+}
+
+/// The `Debug` output of the result, `None` if it panicked.
+fn run_once(
+    f: &(dyn Fn() -> Box<dyn std::fmt::Debug> + Sync),
+    pool: &rayon::ThreadPool,
+    config: Config,
+) -> Option<String> {
+    hash::set_seed(config.hash_seed);
+    let answer = catch_unwind(AssertUnwindSafe(|| pool.install(|| format!("{:?}", f()))));
+    hash::set_seed(0);
+    answer.ok()
+}
+
+/// Runs every variant of the selected days repeatedly with single and
+/// multi-threaded rayon pools and different hash seeds, and reports the
+/// variants whose answers differ between runs.
+pub fn run(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let runs: usize = take_opt(&mut args, "--runs")?.map_or(Ok(4), |x| x.parse())?;
+    let which = args.first().cloned().unwrap_or("all".to_string());
+    let which: Vec<usize> = if which == "all" {
+        (1..=solutions.len()).collect()
+    } else {
+        which
+            .split(',')
+            .filter_map(|x| x.parse::<usize>().ok())
+            .collect()
+    };
+
+    // the size of the global pool, which `--threads` sets
+    let threads = rayon::current_num_threads();
+    let configs = configs(runs, threads);
+    let mut pools = Vec::new();
+    for config in configs.iter() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.threads)
+            .build()?;
+        pools.push(pool);
+    }
+
+    let mut unstable = Vec::new();
+    for day_no in which {
+        println!("Day {}", day_no);
+        for (part_no, part) in solutions[day_no - 1].iter().enumerate() {
+            let part_no = part_no + 1;
+            for (name, solution) in part.iter() {
+                let answers: Vec<Option<String>> = configs
+                    .iter()
+                    .zip(&pools)
+                    .map(|(&config, pool)| run_once(solution.as_ref(), pool, config))
+                    .collect();
+                if answers.iter().all(|a| *a == answers[0]) {
+                    let answer = answers[0].as_deref().unwrap_or("panicked");
+                    println!("  {name}: \x1b[32mstable\x1b[0m {answer}");
+                    continue;
+                }
+                println!("  {name}: \x1b[91munstable\x1b[0m");
+                for (config, answer) in configs.iter().zip(&answers) {
+                    let answer = answer.as_deref().unwrap_or("panicked");
+                    println!("    {config}: {answer}");
+                }
+                unstable.push((day_no, part_no, *name));
+            }
+        }
+        println!();
+    }
+
+    if unstable.is_empty() {
+        println!("All variants gave the same answer in {runs} runs with up to {threads} threads");
+    } else {
+        println!("Unstable:");
+        for (day_no, part_no, name) in unstable {
+            println!("  {day_no} {part_no} {name}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_configs() {
+        let configs = configs(5, 8);
+        let pairs: Vec<_> = configs.iter().map(|c| (c.threads, c.hash_seed)).collect();
+        assert_eq!(pairs, vec![(1, 0), (8, 0), (1, 1), (8, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_run_once() -> Result<()> {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+        let config = Config {
+            threads: 2,
+            hash_seed: 0,
+        };
+        let threads = || Box::new(rayon::current_num_threads()) as Box<dyn std::fmt::Debug>;
+        assert_eq!(run_once(&threads, &pool, config), Some("2".to_string()));
+        let panics = || -> Box<dyn std::fmt::Debug> { panic!("boom") };
+        assert_eq!(run_once(&panics, &pool, config), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Once;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::Result;
+use crate::util::{input_path, take_opt};
+
+/// Parser runs spent on shrinking one reproducer.
+const MINIMIZE_BUDGET: usize = 100_000;
+
+/// Reproducers this short are printed along with their path.
+const SHOW_LEN: usize = 200;
+
+type Parser = fn(&[u8]);
+
+/// `(day, parser)` for every day, dropping whatever `parse` returns.
+macro_rules! parsers {
+    ($($day_no:literal => $day:ident),* $(,)?) => {
+        &[$(($day_no, |input: &[u8]| {
+            let _ = crate::$day::parse(input);
+        })),*]
+    };
+}
+
+/// Day 6 has no parser, it searches the raw stream.
+const PARSERS: &[(usize, Parser)] = parsers!(
+    1 => day01, 2 => day02, 3 => day03, 4 => day04, 5 => day05, 7 => day07,
+    8 => day08, 9 => day09, 10 => day10, 11 => day11, 12 => day12, 13 => day13,
+    14 => day14, 15 => day15, 16 => day16, 17 => day17, 18 => day18, 19 => day19,
+    20 => day20, 21 => day21, 22 => day22, 23 => day23, 24 => day24, 25 => day25,
+);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mutation {
+    /// Flips one bit of one byte.
+    Flip,
+    /// Drops everything after some byte.
+    Truncate,
+    /// Repeats one line right after itself.
+    DuplicateLine,
+}
+
+const MUTATIONS: [Mutation; 3] = [Mutation::Flip, Mutation::Truncate, Mutation::DuplicateLine];
+
+fn mutate(input: &mut Vec<u8>, mutation: Mutation, rng: &mut StdRng) {
+    if input.is_empty() {
+        return;
+    }
+    match mutation {
+        Mutation::Flip => {
+            let i = rng.gen_range(0..input.len());
+            input[i] ^= 1 << rng.gen_range(0..8);
+        }
+        Mutation::Truncate => input.truncate(rng.gen_range(0..input.len())),
+        Mutation::DuplicateLine => {
+            let i = rng.gen_range(0..input.len());
+            let start = input[..i]
+                .iter()
+                .rposition(|&c| c == b'\n')
+                .map_or(0, |j| j + 1);
+            let end = input[i..]
+                .iter()
+                .position(|&c| c == b'\n')
+                .map_or(input.len(), |j| i + j + 1);
+            let mut line = input[start..end].to_vec();
+            if line.last() != Some(&b'\n') {
+                line.insert(0, b'\n');
+            }
+            input.splice(end..end, line);
+        }
+    }
+}
+
+/// One to three random mutations of `input`.
+fn mutant(input: &[u8], rng: &mut StdRng) -> Vec<u8> {
+    let mut input = input.to_vec();
+    for _ in 0..rng.gen_range(1..=3) {
+        let mutation = MUTATIONS[rng.gen_range(0..MUTATIONS.len())];
+        mutate(&mut input, mutation, rng);
+    }
+    input
+}
+
+/// Removes ever smaller runs of `units` as long as the rest still `fails`,
+/// until no single unit can be removed or `budget` candidates have been tried.
+fn reduce(
+    mut units: Vec<&[u8]>,
+    budget: &mut usize,
+    fails: &mut impl FnMut(&[u8]) -> Result<bool>,
+) -> Result<Vec<u8>> {
+    let mut chunk = units.len().div_ceil(2).max(1);
+    loop {
+        let mut i = 0;
+        let mut removed = false;
+        while i < units.len() && *budget > 0 {
+            let end = (i + chunk).min(units.len());
+            let candidate: Vec<u8> = units[..i]
+                .iter()
+                .chain(&units[end..])
+                .flat_map(|u| u.iter().copied())
+                .collect();
+            *budget -= 1;
+            if fails(&candidate)? {
+                units.drain(i..end);
+                removed = true;
+            } else {
+                i += chunk;
+            }
+        }
+        // removing a later unit can make an earlier one removable
+        if chunk == 1 && !removed || *budget == 0 {
+            return Ok(units.concat());
+        }
+        chunk = (chunk / 2).max(1);
+    }
+}
+
+/// Shrinks `input` while `fails` holds, first by whole lines and then by
+/// single bytes.
+fn minimize(
+    input: &[u8],
+    budget: usize,
+    mut fails: impl FnMut(&[u8]) -> Result<bool>,
+) -> Result<Vec<u8>> {
+    let mut budget = budget;
+    let lines = input.split_inclusive(|&c| c == b'\n').collect();
+    let input = reduce(lines, &mut budget, &mut fails)?;
+    let bytes = input.chunks(1).collect();
+    reduce(bytes, &mut budget, &mut fails)
+}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Records the location and message of panics in [`panic_of`] instead of
+/// printing them, other panics still go to the default hook.
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                return default(info);
+            }
+            let at = info.location().map_or(String::new(), |l| l.to_string());
+            let payload = info.payload();
+            let msg = match payload.downcast_ref::<&str>() {
+                Some(msg) => msg.to_string(),
+                None => payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            LAST_PANIC.with(|p| *p.borrow_mut() = Some((at, msg)));
+        }));
+    });
+}
+
+/// Where and with which message `parse` panics on `input`, if it does.
+fn panic_of(parse: Parser, input: &[u8]) -> Option<(String, String)> {
+    install_hook();
+    CATCHING.with(|c| c.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| parse(input)));
+    CATCHING.with(|c| c.set(false));
+    let panic = LAST_PANIC.with(|p| p.borrow_mut().take());
+    result.err().map(|_| panic.unwrap_or_default())
+}
+
+/// Real and example input of a day, those that exist.
+fn seeds(day_no: usize) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut seeds = Vec::new();
+    for path in [input_path(day_no), format!("inputs/day{:02}x", day_no)] {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            let data = fs::read(&path)?;
+            seeds.push((path, data));
+        }
+    }
+    Ok(seeds)
+}
+
+/// A parser panic and the smallest input found that panics at the same
+/// location.
+struct Finding {
+    at: String,
+    msg: String,
+    seed: PathBuf,
+    mutant_len: usize,
+    reproducer: Vec<u8>,
+}
+
+/// Feeds `iterations` mutants of `seeds` to `parse` and minimizes the first
+/// mutant for every panic location.
+fn fuzz_day(
+    parse: Parser,
+    seeds: &[(PathBuf, Vec<u8>)],
+    iterations: usize,
+    rng: &mut StdRng,
+) -> Result<Vec<Finding>> {
+    let mut seen = BTreeSet::new();
+    let mut findings = Vec::new();
+    for _ in 0..iterations {
+        let (seed, seed_data) = &seeds[rng.gen_range(0..seeds.len())];
+        let input = mutant(seed_data, rng);
+        let Some((at, msg)) = panic_of(parse, &input) else {
+            continue;
+        };
+        if !seen.insert(at.clone()) {
+            continue;
+        }
+        let reproducer = minimize(&input, MINIMIZE_BUDGET, |candidate| {
+            Ok(panic_of(parse, candidate).is_some_and(|(a, _)| a == at))
+        })?;
+        findings.push(Finding {
+            at,
+            msg,
+            seed: seed.clone(),
+            mutant_len: input.len(),
+            reproducer,
+        });
+    }
+    Ok(findings)
+}
+
+/// Runs the parser of every selected day on randomly mutated copies of its
+/// real and example inputs, and minimizes the inputs that make it panic.
+pub fn run(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let seed: u64 = take_opt(&mut args, "--seed")?.map_or(Ok(0), |x| x.parse())?;
+    let iterations: usize = take_opt(&mut args, "--iterations")?.map_or(Ok(1000), |x| x.parse())?;
+    let out = PathBuf::from(take_opt(&mut args, "--out")?.unwrap_or("inputs/fuzz".to_string()));
+    let which = args.first().cloned().unwrap_or("all".to_string());
+    let which: Vec<usize> = if which == "all" {
+        PARSERS.iter().map(|&(day_no, _)| day_no).collect()
+    } else {
+        which
+            .split(',')
+            .filter_map(|x| x.parse::<usize>().ok())
+            .collect()
+    };
+
+    let mut findings = Vec::new();
+    for day_no in which {
+        let Some(&(_, parse)) = PARSERS.iter().find(|&&(d, _)| d == day_no) else {
+            println!("Day {day_no} has no parser\n");
+            continue;
+        };
+        let seeds = seeds(day_no)?;
+        if seeds.is_empty() {
+            continue;
+        }
+        println!("Day {}", day_no);
+        // a fixed stream per day, so that `--seed` reproduces a day on its own
+        let mut rng = StdRng::seed_from_u64(seed ^ ((day_no as u64) << 32));
+        for (i, finding) in fuzz_day(parse, &seeds, iterations, &mut rng)?
+            .into_iter()
+            .enumerate()
+        {
+            let path = out
+                .join(format!("day{:02}", day_no))
+                .join(format!("parse-{}", i + 1));
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, &finding.reproducer)?;
+            let reason = format!("panicked at {}: {}", finding.at, finding.msg);
+            println!("  {reason}");
+            println!(
+                "    mutant of {} shrunk from {} to {} bytes: {}",
+                finding.seed.display(),
+                finding.mutant_len,
+                finding.reproducer.len(),
+                path.display()
+            );
+            if finding.reproducer.len() <= SHOW_LEN {
+                println!("    {:?}", String::from_utf8_lossy(&finding.reproducer));
+            }
+            findings.push((day_no, reason, path));
+        }
+        println!();
+    }
+
+    if findings.is_empty() {
+        println!("No panics found with seed {seed}");
+    } else {
+        println!("Panics found with seed {seed}:");
+        for (day_no, reason, path) in findings {
+            println!("  {day_no} 1 --input {}", path.display());
+            println!("    {reason}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mutate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let input = b"1,2\n3,4\n".to_vec();
+
+        let mut flipped = input.clone();
+        mutate(&mut flipped, Mutation::Flip, &mut rng);
+        let diff: Vec<_> = input.iter().zip(&flipped).map(|(a, b)| a ^ b).collect();
+        assert_eq!(diff.iter().map(|x| x.count_ones()).sum::<u32>(), 1);
+
+        let mut truncated = input.clone();
+        mutate(&mut truncated, Mutation::Truncate, &mut rng);
+        assert!(input.starts_with(&truncated) && truncated.len() < input.len());
+
+        for input in [&b"1,2\n3,4\n"[..], b"1,2\n3,4"] {
+            let mut duplicated = input.to_vec();
+            mutate(&mut duplicated, Mutation::DuplicateLine, &mut rng);
+            let lines = duplicated.split(|&c| c == b'\n').filter(|l| !l.is_empty());
+            assert_eq!(lines.count(), 3);
+        }
+
+        let a = mutant(&input, &mut StdRng::seed_from_u64(7));
+        let b = mutant(&input, &mut StdRng::seed_from_u64(7));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_minimize() -> Result<()> {
+        let input = b"1,2,3\n4,5,6\n7,99,8\n9,9,9\n";
+        let fails = |s: &[u8]| Ok(s.windows(2).any(|w| w == b"99"));
+        assert_eq!(minimize(input, 1000, fails)?, b"99");
+
+        let mut runs = 0;
+        let small = minimize(input, 3, |s| {
+            runs += 1;
+            Ok(s.windows(2).any(|w| w == b"99"))
+        })?;
+        assert_eq!(runs, 3);
+        assert!(small.windows(2).any(|w| w == b"99"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_day18() -> Result<()> {
+        // coordinates of 22 and above are out of the map
+        let (_, parse) = PARSERS.iter().find(|&&(d, _)| d == 18).unwrap();
+        let seeds = [(PathBuf::from("day18x"), b"2,2,2\n1,2,20\n".to_vec())];
+        let findings = fuzz_day(*parse, &seeds, 200, &mut StdRng::seed_from_u64(13))?;
+        let finding = findings
+            .iter()
+            .find(|f| f.msg.contains("the len is 22"))
+            .unwrap();
+        assert!(finding.at.starts_with("src/day18.rs"));
+        assert_eq!(finding.reproducer, b"22");
+        Ok(())
+    }
+}
@@ -6,8 +6,9 @@
 #![feature(iter_collect_into)]
 
 use std::collections::btree_map::BTreeMap;
-use std::collections::HashMap;
 
+mod bench;
+mod corpus;
 mod day01;
 mod day02;
 mod day03;
@@ -33,21 +34,24 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod determinism;
 mod error;
+mod fuzz;
+mod meta;
+mod scale;
+mod trace;
 mod util;
+mod viz;
+mod watch;
 
-use std::hint::black_box;
-use std::{
-    fmt::Debug,
-    time::{Duration, Instant},
-};
-use util::format_duration;
+use std::{fmt::Debug, time::Instant};
+use util::{format_duration, input_path, preload, take_opt, take_switch};
 
 use crate::error::Result;
 
 macro_rules! dynfn {
     ($x:expr) => {
-        Box::new(|| Box::new($x()) as Box<dyn Debug>) as Box<dyn Fn() -> Box<dyn Debug>>
+        Box::new(|| Box::new($x()) as Box<dyn Debug>) as Solution
     };
 }
 
@@ -80,8 +84,35 @@ macro_rules! dynfns {
     };
 }
 
+/// `Sync` so that a solution can be run inside a rayon pool.
+pub type Solution = Box<dyn Fn() -> Box<dyn Debug> + Send + Sync>;
+pub type Solutions = Vec<Vec<BTreeMap<&'static str, Solution>>>;
+
+/// Looks up a solution by day and part. `variant` may be the full name
+/// (`day02::solve_b_opt`) or just the function name; without it the first
+/// registered variant is used.
+pub fn find_solution<'a>(
+    solutions: &'a Solutions,
+    day_no: usize,
+    part_no: usize,
+    variant: Option<&str>,
+) -> Result<(&'static str, &'a Solution)> {
+    let part = solutions
+        .get(day_no.wrapping_sub(1))
+        .and_then(|day| day.get(part_no.wrapping_sub(1)))
+        .ok_or(format!("no solution for day {day_no} part {part_no}"))?;
+    let (name, f) = match variant {
+        Some(v) => part
+            .iter()
+            .find(|(name, _)| **name == v || name.ends_with(&format!("::{v}")))
+            .ok_or(format!("no variant {v}"))?,
+        None => part.iter().next().unwrap(),
+    };
+    Ok((*name, f))
+}
+
 fn main() -> Result<()> {
-    let solutions: Vec<Vec<BTreeMap<_, Box<dyn Fn() -> Box<dyn Debug>>>>> = vec![
+    let solutions: Solutions = vec![
         vec![dynfns!(day01::solve_a)],
         vec![
             dynfns!(day02::solve_a),
@@ -91,7 +122,10 @@ fn main() -> Result<()> {
         vec![dynfns!(day04::solve_a)],
         vec![dynfns!(day05::solve_a), dynfns!(day05::solve_b)],
         vec![dynfns!(day06::solve_a), dynfns!(day06::solve_b)],
-        vec![dynfns!(day07::solve_a), dynfns!(day07::solve_b)],
+        vec![
+            dynfns!(day07::solve_a, day07::solve_a_arena),
+            dynfns!(day07::solve_b, day07::solve_b_arena),
+        ],
         vec![
             dynfns!(day08::solve_a, day08::solve_a_opt),
             dynfns!(day08::solve_b),
@@ -100,7 +134,10 @@ fn main() -> Result<()> {
         vec![dynfns!(day10::solve_a), dynfns!(day10::solve_b)],
         vec![dynfns!(day11::solve_a), dynfns!(day11::solve_b)],
         vec![dynfns!(day12::solve_a), dynfns!(day12::solve_b)],
-        vec![dynfns!(day13::solve_a), dynfns!(day13::solve_b)],
+        vec![
+            dynfns!(day13::solve_a, day13::solve_a_arena),
+            dynfns!(day13::solve_b, day13::solve_b_arena),
+        ],
         vec![dynfns!(day14::solve_a), dynfns!(day14::solve_b)],
         vec![dynfns!(day15::solve_a), dynfns!(day15::solve_b)],
         vec![dynfns!(day16::solve_a), dynfns!(day16::solve_b)],
@@ -115,133 +152,72 @@ fn main() -> Result<()> {
         vec![dynfns!(day25::solve_a)],
     ];
 
-    let mut args = std::env::args();
-    args.next().unwrap();
-    let which = args.next().unwrap_or("1".to_string());
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if take_switch(&mut args, "-v") {
+        trace::enable(None);
+    }
+    if take_switch(&mut args, "--siphash") {
+        util::hash::use_siphash(true);
+    }
+    if let Some(threads) = take_opt(&mut args, "--threads")? {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.parse()?)
+            .build_global()?;
+    }
+    if let Some(days) = take_opt(&mut args, "--trace")? {
+        let days = days
+            .split(',')
+            .map(|x| x.parse())
+            .collect::<std::result::Result<_, _>>()?;
+        trace::enable(Some(days));
+    }
+    let which = if args.is_empty() {
+        "1".to_string()
+    } else {
+        args.remove(0)
+    };
 
     if which == "bench" {
-        let mut results = HashMap::new();
-        let which = args.next().unwrap_or("all".to_string());
-        let which = which.split(",").collect::<Vec<_>>();
-        let which: Vec<_> = if which[0] == "all" {
-            (0..solutions.len()).collect()
-        } else {
-            which
-                .iter()
-                .filter_map(|x| x.parse::<usize>().ok().map(|x| x - 1))
-                .collect()
-        };
-        let mut total = Duration::ZERO;
-        let duration_per_test = Duration::from_millis(5000);
-        let sample_chunk = 1;
-        for i in which.iter() {
-            let day = &solutions[*i];
-            let day_no = i + 1;
-            println!("Day {}", day_no);
-            for (part_no, part) in day.iter().enumerate() {
-                let part_no = part_no + 1;
-                for (name, solution) in part {
-                    let mut samples = 0;
-                    let mut elapsed = Duration::ZERO;
-                    let tic = Instant::now();
-                    for _ in 0..sample_chunk {
-                        black_box(solution());
-                    }
-                    let single_sample = tic.elapsed();
-                    elapsed += single_sample;
-                    samples += 1;
-
-                    let sample_chunk = 1
-                        .max((duration_per_test.as_nanos() / single_sample.as_nanos() / 10) as u32);
-                    while elapsed < duration_per_test {
-                        let tic = Instant::now();
-                        for _ in 0..sample_chunk {
-                            black_box(solution());
-                        }
-                        let chunk_elapsed = tic.elapsed();
-                        elapsed += chunk_elapsed;
-                        samples += sample_chunk;
-                    }
-                    let avg = elapsed / samples;
-                    results
-                        .entry((day_no, part_no))
-                        .and_modify(|x| {
-                            if *x > avg {
-                                *x = avg;
-                            }
-                        })
-                        .or_insert(avg);
-                    total += avg;
-                    println!(
-                        "{name} computed in {} ({samples} samples)",
-                        format_duration(avg)
-                    );
-                }
-            }
-            println!("");
-        }
-
-        println!("Total: {}", format_duration(total));
-        println!("");
-        println!("");
-        println!("Day     Part 1      Part 2");
-
-        let mut times = results.values().copied().collect::<Vec<_>>();
-        times.sort();
-        let lower_quartile = times[times.len() / 4];
-        let upper_quartile = times[times.len() * 3 / 4];
-        let mut total_best = Duration::ZERO;
-        for i in which.iter() {
-            let day_no = i + 1;
-            print!("{: >2}", day_no);
-            for part_no in [1, 2] {
-                match results.get(&(day_no, part_no)) {
-                    Some(x) => {
-                        total_best += *x;
-                        print!(
-                            "  \x1b[{}m{: >10}\x1b[0m",
-                            if *x <= lower_quartile {
-                                32
-                            } else if *x >= upper_quartile {
-                                91
-                            } else {
-                                93
-                            },
-                            format_duration(*x)
-                        );
-                    }
-                    None => {
-                        print!("         n/a");
-                    }
-                }
-            }
-            println!("");
-        }
-        println!("");
-        println!("Total: {}", format_duration(total_best));
-        return Ok(());
+        return bench::run(&solutions, args.into_iter());
+    }
+    if which == "list" {
+        return meta::list(&solutions, args.into_iter());
+    }
+    if which == "profile" {
+        return bench::profile(&solutions, args.into_iter());
+    }
+    if which == "corpus" {
+        return corpus::run(&solutions, args.into_iter());
+    }
+    if which == "determinism" {
+        return determinism::run(&solutions, args.into_iter());
     }
+    if which == "fuzz" {
+        return fuzz::run(args.into_iter());
+    }
+    if which == "scale" {
+        return scale::run(&solutions, args.into_iter());
+    }
+    if which == "viz" {
+        return viz::run(args.into_iter());
+    }
+    if which == "watch" {
+        return watch::run(args.into_iter());
+    }
+    let variant = take_opt(&mut args, "--variant")?;
     let which: usize = which.parse()?;
-    let which_sub: usize = args.next().unwrap_or("1".to_string()).parse()?;
+    if let Some(input) = take_opt(&mut args, "--input")? {
+        preload(input_path(which), std::fs::read(input)?);
+    }
+    let which_sub: usize = args.first().map_or("1", |x| x.as_str()).parse()?;
 
-    let (_, f) = solutions[which - 1][which_sub - 1].iter().nth(0).unwrap();
+    let (_, f) = find_solution(&solutions, which, which_sub, variant.as_deref())?;
     let tic = Instant::now();
     let res = f();
     let elapsed = tic.elapsed();
     println!("Result: {:?}", res);
     println!("Computed in {}", format_duration(elapsed));
-
-    // day01::solve_a()?;
-    // day02::solve_a()?;
-    // day02::solve_b_opt()?;
-    // gen_lut();
-    // day02::solve_b()?;
-    // dbg!(day03::solve_a()?);
-    // dbg!(day03::solve_b()?);
-    // dbg!(day04::solve_a()?);
-    // dbg!(day05::solve_b()?);
-    // assert_eq!(orig, opt);
-    // gen_input_day2()?;
+    println!("{}", bench::self_usage()?);
     Ok(())
 }
 
@@ -252,14 +228,12 @@ mod test {
     fn test() -> Result<()> {
         day01::solve_a()?;
         day02::solve_a()?;
-        // day02::solve_b_opt()?;
         day02::gen_lut();
         day02::solve_b()?;
         dbg!(day03::solve_a()?);
         dbg!(day03::solve_b()?);
         dbg!(day04::solve_a()?);
         dbg!(day05::solve_b()?);
-        // gen_input_day2()?;
         Ok(())
     }
 }
@@ -0,0 +1,209 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Result;
+use crate::util::take_opt;
+use crate::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25, Solutions,
+};
+
+/// What a day declares about itself, next to the solutions registered in
+/// `main`.
+pub struct DayInfo {
+    pub title: &'static str,
+    pub tags: &'static [Tag],
+    pub status: Status,
+}
+
+/// Techniques a solution relies on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tag {
+    Grid,
+    GraphSearch,
+    Dp,
+    Simulation,
+    CycleDetection,
+    Parsing,
+    Intervals,
+    Bitwise,
+    SlidingWindow,
+}
+
+const TAGS: [Tag; 9] = [
+    Tag::Grid,
+    Tag::GraphSearch,
+    Tag::Dp,
+    Tag::Simulation,
+    Tag::CycleDetection,
+    Tag::Parsing,
+    Tag::Intervals,
+    Tag::Bitwise,
+    Tag::SlidingWindow,
+];
+
+impl Tag {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tag::Grid => "grid",
+            Tag::GraphSearch => "graph-search",
+            Tag::Dp => "dp",
+            Tag::Simulation => "simulation",
+            Tag::CycleDetection => "cycle-detection",
+            Tag::Parsing => "parsing",
+            Tag::Intervals => "intervals",
+            Tag::Bitwise => "bitwise",
+            Tag::SlidingWindow => "sliding-window",
+        }
+    }
+}
+
+impl FromStr for Tag {
+    type Err = String;
+
+    /// The [`Tag::name`], also with a space or underscore for the dash.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.to_lowercase().replace([' ', '_'], "-");
+        TAGS.into_iter().find(|t| t.name() == s).ok_or_else(|| {
+            let names: Vec<_> = TAGS.iter().map(|t| t.name()).collect();
+            format!("unknown tag {s}, expected one of {}", names.join(", "))
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    /// Part 1 returns the answers of both parts.
+    Combined,
+    /// The part is registered but returns a placeholder answer.
+    Stubbed(usize),
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Solved => write!(f, "solved"),
+            Status::Combined => write!(f, "solved, both parts in part 1"),
+            Status::Stubbed(part_no) => write!(f, "part {part_no} stubbed"),
+        }
+    }
+}
+
+pub const DAYS: [&DayInfo; 25] = [
+    &day01::INFO,
+    &day02::INFO,
+    &day03::INFO,
+    &day04::INFO,
+    &day05::INFO,
+    &day06::INFO,
+    &day07::INFO,
+    &day08::INFO,
+    &day09::INFO,
+    &day10::INFO,
+    &day11::INFO,
+    &day12::INFO,
+    &day13::INFO,
+    &day14::INFO,
+    &day15::INFO,
+    &day16::INFO,
+    &day17::INFO,
+    &day18::INFO,
+    &day19::INFO,
+    &day20::INFO,
+    &day21::INFO,
+    &day22::INFO,
+    &day23::INFO,
+    &day24::INFO,
+    &day25::INFO,
+];
+
+/// One `<part> <variant>` line per registered variant of a day, the format
+/// of `list --variants` that `watch` reads back.
+pub fn variant_lines(solutions: &Solutions, day_no: usize) -> Result<Vec<String>> {
+    let day = solutions
+        .get(day_no.wrapping_sub(1))
+        .ok_or(format!("no solution for day {day_no}"))?;
+    let mut lines = Vec::new();
+    for (part_no, part) in (1..).zip(day) {
+        for name in part.keys() {
+            lines.push(format!("{part_no} {}", name.rsplit("::").next().unwrap()));
+        }
+    }
+    Ok(lines)
+}
+
+/// Prints title, status, tags and variants of every day, or of the days
+/// that have all tags given with `--tag`. `--variants <day>` prints only the
+/// variants of that day, one per line.
+pub fn list(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    if let Some(day_no) = take_opt(&mut args, "--variants")? {
+        for line in variant_lines(solutions, day_no.parse()?)? {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+    let tags: Vec<Tag> = match take_opt(&mut args, "--tag")? {
+        Some(tags) => tags
+            .split(',')
+            .map(|x| x.parse())
+            .collect::<std::result::Result<_, _>>()?,
+        None => Vec::new(),
+    };
+
+    for (day_no, info) in DAYS.iter().enumerate() {
+        let day_no = day_no + 1;
+        if !tags.iter().all(|t| info.tags.contains(t)) {
+            continue;
+        }
+        let status = match info.status {
+            Status::Solved | Status::Combined => info.status.to_string(),
+            _ => format!("\x1b[93m{}\x1b[0m", info.status),
+        };
+        let day_tags: Vec<_> = info.tags.iter().map(|t| t.name()).collect();
+        println!("{day_no:>2} {:<26} {status}", info.title);
+        if !day_tags.is_empty() {
+            println!("   tags: {}", day_tags.join(", "));
+        }
+        for (part_no, part) in solutions[day_no - 1].iter().enumerate() {
+            let names: Vec<_> = part
+                .keys()
+                .map(|x| x.rsplit("::").next().unwrap())
+                .collect();
+            println!("   part {}: {}", part_no + 1, names.join(", "));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tags() {
+        for tag in TAGS {
+            assert_eq!(tag.name().parse(), Ok(tag));
+        }
+        assert_eq!("Graph search".parse(), Ok(Tag::GraphSearch));
+        assert!("sorting".parse::<Tag>().is_err());
+        let dp: Vec<_> = (1..=25)
+            .filter(|&d| DAYS[d - 1].tags.contains(&Tag::Dp))
+            .collect();
+        assert_eq!(dp, vec![16, 19]);
+        assert_eq!("sliding window".parse(), Ok(Tag::SlidingWindow));
+        for (day_no, info) in (1..).zip(DAYS) {
+            assert!(!info.tags.is_empty(), "day {day_no} has no tags");
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(DAYS[9].status.to_string(), "part 2 stubbed");
+        assert_eq!(DAYS[21].status, Status::Stubbed(2));
+        assert_eq!(DAYS[0].status, Status::Combined);
+        assert_eq!(DAYS[3].status, Status::Combined);
+        assert_eq!(DAYS[24].status, Status::Solved);
+    }
+}
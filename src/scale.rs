@@ -0,0 +1,181 @@
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::util::{format_duration, input_path, preload, take_opt, unload};
+use crate::{day01, day02, day04, day06, day09, day15, day20, day25, Solutions};
+
+/// Input generator of a day, parameterized by problem size.
+struct Generator {
+    day_no: usize,
+    /// Parts for which the generated inputs are meaningful.
+    parts: &'static [usize],
+    /// Smallest problem size, doubled on each step.
+    base: usize,
+    gen: fn(usize) -> Vec<u8>,
+}
+
+const GENERATORS: [Generator; 8] = [
+    Generator {
+        day_no: 1,
+        parts: &[1],
+        base: 1000,
+        gen: day01::gen_input,
+    },
+    Generator {
+        day_no: 2,
+        parts: &[1, 2],
+        base: 1000,
+        gen: day02::gen_input,
+    },
+    Generator {
+        day_no: 4,
+        parts: &[1],
+        base: 1000,
+        gen: day04::gen_input,
+    },
+    Generator {
+        day_no: 6,
+        parts: &[1, 2],
+        base: 1000,
+        gen: day06::gen_input,
+    },
+    Generator {
+        day_no: 9,
+        parts: &[1, 2],
+        base: 1000,
+        gen: day09::gen_input,
+    },
+    Generator {
+        day_no: 15,
+        parts: &[2],
+        base: 8,
+        gen: day15::gen_input,
+    },
+    Generator {
+        day_no: 20,
+        parts: &[1, 2],
+        base: 250,
+        gen: day20::gen_input,
+    },
+    Generator {
+        day_no: 25,
+        parts: &[1],
+        base: 1000,
+        gen: day25::gen_input,
+    },
+];
+
+/// Average runtime of `f`, repeated until at least 50ms have been spent.
+/// Returns `None` if it panics.
+fn time(f: &dyn Fn() -> Box<dyn std::fmt::Debug>) -> Option<Duration> {
+    let min_elapsed = Duration::from_millis(50);
+    let mut runs = 0;
+    let tic = Instant::now();
+    while runs == 0 || tic.elapsed() < min_elapsed {
+        catch_unwind(AssertUnwindSafe(|| black_box(f()))).ok()?;
+        runs += 1;
+    }
+    Some(tic.elapsed() / runs)
+}
+
+/// Least squares fit of `t = c * n^k`, returns `k`.
+fn fit_exponent(points: &[(usize, Duration)]) -> f64 {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .map(|(n, t)| ((*n as f64).ln(), t.as_secs_f64().ln()))
+        .collect();
+    let len = logs.len() as f64;
+    let mean_x = logs.iter().map(|x| x.0).sum::<f64>() / len;
+    let mean_y = logs.iter().map(|x| x.1).sum::<f64>() / len;
+    let cov: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let var: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    cov / var
+}
+
+/// Times every variant of the selected days on generated inputs of doubling
+/// size and fits the empirical complexity exponent.
+pub fn run(solutions: &Solutions, args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let steps: u32 = take_opt(&mut args, "--steps")?.map_or(Ok(8), |x| x.parse())?;
+    let budget = take_opt(&mut args, "--budget")?
+        .map(|x| x.parse::<f64>().map(Duration::from_secs_f64))
+        .transpose()?
+        .unwrap_or(Duration::from_secs(2));
+    let csv_path = take_opt(&mut args, "--csv")?;
+    let which = args.first().cloned().unwrap_or("all".to_string());
+    let which: Vec<usize> = if which == "all" {
+        GENERATORS.iter().map(|g| g.day_no).collect()
+    } else {
+        which
+            .split(',')
+            .filter_map(|x| x.parse::<usize>().ok())
+            .collect()
+    };
+
+    let mut csv = String::from("day,part,variant,n,seconds\n");
+    for generator in GENERATORS.iter().filter(|g| which.contains(&g.day_no)) {
+        let day_no = generator.day_no;
+        println!("Day {}", day_no);
+        let inputs: Vec<(usize, Vec<u8>)> = (0..steps)
+            .map(|k| generator.base << k)
+            .map(|n| (n, (generator.gen)(n)))
+            .collect();
+        for &part_no in generator.parts {
+            for (name, solution) in solutions[day_no - 1][part_no - 1].iter() {
+                let mut points = Vec::new();
+                for (n, input) in inputs.iter() {
+                    preload(input_path(day_no), input.clone());
+                    let elapsed = match time(solution.as_ref()) {
+                        Some(x) => x,
+                        None => {
+                            println!("  {name} panicked at n = {n}");
+                            break;
+                        }
+                    };
+                    writeln!(
+                        csv,
+                        "{day_no},{part_no},{name},{n},{}",
+                        elapsed.as_secs_f64()
+                    )?;
+                    points.push((*n, elapsed));
+                    if elapsed > budget {
+                        break;
+                    }
+                }
+                let curve = points
+                    .iter()
+                    .map(|(n, t)| format!("{n}: {}", format_duration(*t).trim()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if points.len() >= 2 {
+                    println!("  {name} ~ n^{:.2} ({curve})", fit_exponent(&points));
+                } else {
+                    println!("  {name} ({curve})");
+                }
+            }
+        }
+        unload(input_path(day_no));
+        println!();
+    }
+
+    if let Some(path) = csv_path {
+        std::fs::write(path, csv)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit_exponent() {
+        let quadratic: Vec<_> = (1..6)
+            .map(|k| (1 << k, Duration::from_micros(3 * (1 << (2 * k)))))
+            .collect();
+        assert!((fit_exponent(&quadratic) - 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,125 @@
+//! Structured debug output for the solvers.
+//!
+//! Events are compiled in everywhere but cost a single relaxed load unless
+//! tracing was enabled with `-v` (all days) or `--trace <days>`. Everything
+//! goes to stderr, prefixed with the emitting module:
+//!
+//! ```text
+//! [day12] pop pos=(0, 20) steps=0
+//! [day12] begin search
+//! [day12] end search (1.32ms)
+//! ```
+
+use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+
+use crate::util::format_duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    /// Modules to trace, `None` traces all of them.
+    static ref FILTER: RwLock<Option<Vec<String>>> = RwLock::new(None);
+}
+
+/// Enables tracing for the given days, or for all days if `None`.
+pub fn enable(days: Option<Vec<usize>>) {
+    *FILTER.write().unwrap() = days.map(|d| d.iter().map(|d| format!("day{:02}", d)).collect());
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn label(module: &str) -> &str {
+    module.rsplit("::").next().unwrap_or(module)
+}
+
+pub fn enabled(module: &str) -> bool {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+    match &*FILTER.read().unwrap() {
+        Some(filter) => filter.iter().any(|x| x == label(module)),
+        None => true,
+    }
+}
+
+pub fn emit(module: &str, name: &str, fields: &[(&str, &dyn Debug)]) {
+    let mut line = format!("[{}] {}", label(module), name);
+    for (key, value) in fields {
+        line += &format!(" {key}={value:?}");
+    }
+    eprintln!("{line}");
+}
+
+pub fn emit_snapshot(module: &str, name: &str, value: &dyn Display) {
+    eprintln!("[{}] {}\n{}", label(module), name, value);
+}
+
+/// Emits `begin` on creation and `end` with the elapsed time on drop.
+pub struct Phase {
+    module: &'static str,
+    name: &'static str,
+    tic: Instant,
+}
+
+impl Phase {
+    pub fn new(module: &'static str, name: &'static str) -> Option<Self> {
+        if !enabled(module) {
+            return None;
+        }
+        emit(module, &format!("begin {name}"), &[]);
+        Some(Self {
+            module,
+            name,
+            tic: Instant::now(),
+        })
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        let elapsed = format_duration(self.tic.elapsed());
+        emit(
+            self.module,
+            &format!("end {} ({})", self.name, elapsed.trim()),
+            &[],
+        );
+    }
+}
+
+/// `event!("name", key = value, ...)` emits a single line with the `Debug`
+/// representation of each field.
+macro_rules! trace_event {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled(module_path!()) {
+            $crate::trace::emit(
+                module_path!(),
+                $name,
+                &[$((stringify!($key), &$value as &dyn std::fmt::Debug)),*],
+            );
+        }
+    };
+}
+
+/// `snapshot!("name", value)` emits the multi-line `Display` representation
+/// of a state. `value` is only evaluated if tracing is enabled.
+macro_rules! trace_snapshot {
+    ($name:expr, $value:expr) => {
+        if $crate::trace::enabled(module_path!()) {
+            $crate::trace::emit_snapshot(module_path!(), $name, &$value);
+        }
+    };
+}
+
+/// `let _phase = phase!("name");` marks a phase boundary for the rest of the
+/// scope.
+macro_rules! trace_phase {
+    ($name:expr) => {
+        $crate::trace::Phase::new(module_path!(), $name)
+    };
+}
+
+pub(crate) use trace_event as event;
+pub(crate) use trace_phase as phase;
+pub(crate) use trace_snapshot as snapshot;
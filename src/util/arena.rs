@@ -0,0 +1,149 @@
+//! A bump allocator for trees that are built once and dropped as a whole.
+//!
+//! `&Arena` implements [`Allocator`], so nodes go into `Box::new_in(x,
+//! &arena)` and child lists into `Vec::new_in(&arena)`. Freeing is a no-op,
+//! all memory is returned when the arena is dropped.
+
+use std::alloc::{self, AllocError, Allocator, Layout};
+use std::cell::{Cell, RefCell};
+use std::ptr::NonNull;
+
+/// Size of the first chunk, every further chunk is twice as large.
+const FIRST_CHUNK: usize = 4096;
+
+/// Alignment of every chunk, larger alignments are padded inside the chunk.
+const CHUNK_ALIGN: usize = 16;
+
+pub struct Arena {
+    /// Start and layout of every chunk, for freeing them on drop.
+    chunks: RefCell<Vec<(NonNull<u8>, Layout)>>,
+    /// Free space of the current chunk, null before the first one.
+    next: Cell<*mut u8>,
+    end: Cell<*mut u8>,
+    allocated: Cell<usize>,
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            next: Cell::new(std::ptr::null_mut()),
+            end: Cell::new(std::ptr::null_mut()),
+            allocated: Cell::new(0),
+        }
+    }
+
+    /// Bytes handed out so far, including those of grown and freed
+    /// allocations.
+    pub fn allocated(&self) -> usize {
+        self.allocated.get()
+    }
+
+    /// Bytes reserved from the system.
+    pub fn reserved(&self) -> usize {
+        self.chunks.borrow().iter().map(|(_, l)| l.size()).sum()
+    }
+
+    pub fn chunks(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+
+    fn bump(&self, layout: Layout) -> NonNull<u8> {
+        let next = self.next.get();
+        let available = self.end.get() as usize - next as usize;
+        let pad = next.align_offset(layout.align());
+        match pad.checked_add(layout.size()) {
+            Some(needed) if needed <= available && !next.is_null() => unsafe {
+                let start = next.add(pad);
+                self.next.set(start.add(layout.size()));
+                self.allocated.set(self.allocated.get() + layout.size());
+                NonNull::new_unchecked(start)
+            },
+            _ => {
+                self.grow(layout);
+                self.bump(layout)
+            }
+        }
+    }
+
+    /// Starts a new chunk that fits at least `layout`.
+    #[cold]
+    fn grow(&self, layout: Layout) {
+        let mut chunks = self.chunks.borrow_mut();
+        let size = chunks
+            .last()
+            .map_or(FIRST_CHUNK, |(_, l)| l.size() * 2)
+            .max(layout.size() + layout.align());
+        let chunk = Layout::from_size_align(size, CHUNK_ALIGN).unwrap();
+        let ptr = NonNull::new(unsafe { alloc::alloc(chunk) })
+            .unwrap_or_else(|| alloc::handle_alloc_error(chunk));
+        chunks.push((ptr, chunk));
+        self.next.set(ptr.as_ptr());
+        self.end.set(unsafe { ptr.as_ptr().add(size) });
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        for (ptr, layout) in self.chunks.get_mut().drain(..) {
+            unsafe { alloc::dealloc(ptr.as_ptr(), layout) };
+        }
+    }
+}
+
+unsafe impl Allocator for Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(NonNull::slice_from_raw_parts(
+            self.bump(layout),
+            layout.size(),
+        ))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_alloc() {
+        let arena = Arena::new();
+        let mut a = Box::new_in(1u8, &arena);
+        let b = Box::new_in(2u64, &arena);
+        *a += 1;
+        assert_eq!((*a, *b), (2, 2));
+        assert_eq!(&*b as *const u64 as usize % 8, 0);
+
+        #[repr(align(64))]
+        struct Aligned(u8);
+        let c = Box::new_in(Aligned(3), &arena);
+        assert_eq!((&*c as *const Aligned as usize % 64, c.0), (0, 3));
+
+        let big = Box::new_in([7u8; 3 * FIRST_CHUNK], &arena);
+        assert_eq!(big[3 * FIRST_CHUNK - 1], 7);
+        assert!(arena.chunks() >= 2);
+        assert!(arena.reserved() >= arena.allocated());
+    }
+
+    #[test]
+    fn test_collections() {
+        let arena = Arena::new();
+        let mut v = Vec::new_in(&arena);
+        for i in 0..10000u32 {
+            v.push(Box::new_in(i, &arena));
+        }
+        assert_eq!(v.iter().map(|x| **x as u64).sum::<u64>(), 49995000);
+        let mut w = v.clone();
+        w.sort_by(|a, b| b.cmp(a));
+        assert_eq!(*w[0], 9999);
+        let empty: Vec<u64, _> = Vec::new_in(&arena);
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Cycle detection for iterated functions `x, f(x), f(f(x)), ...`.
+//!
+//! The detection assumes that the sequence eventually repeats, which holds
+//! for any function over a finite set of states.
+
+/// The sequence enters a loop after `offset` steps and repeats every
+/// `length` steps from there on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Result of [`extrapolate`].
+#[derive(Debug)]
+pub struct Extrapolation<A> {
+    /// The quantity accumulated over all `n` steps.
+    pub total: A,
+    /// The detected cycle, or offset `n` if it was not found within `n`
+    /// steps. The offset is where the cycle was noticed, which may be later
+    /// than where it starts.
+    pub cycle: Cycle,
+}
+
+/// Applies `f` `n` times starting at `x0`, where each step updates the state
+/// in place and yields a quantity such as a height gain. The quantities are
+/// combined with `accumulate(total, quantity, times)`, which adds `quantity`
+/// `times` times to `total`, starting from `A::default()`. Whole cycles are
+/// skipped by accumulating the quantity of one cycle at once, so `n` can be
+/// huge.
+///
+/// The cycle is found with Brent's algorithm while stepping, so the state is
+/// only cloned at its power-of-two checkpoints. States are compared with `Eq`,
+/// which must ignore the accumulated parts.
+pub fn extrapolate<S: Clone + Eq, A: Default>(
+    mut f: impl FnMut(&mut S) -> A,
+    x0: S,
+    n: usize,
+    mut accumulate: impl FnMut(&mut A, &A, usize),
+) -> Extrapolation<A> {
+    let mut total = A::default();
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    // quantities of the steps since the tortoise was placed at `checkpoint`
+    let mut window = Vec::new();
+    let mut checkpoint = 0;
+    let mut power = 1;
+    loop {
+        if checkpoint + window.len() == n {
+            for quantity in window.iter() {
+                accumulate(&mut total, quantity, 1);
+            }
+            return Extrapolation {
+                total,
+                cycle: Cycle {
+                    offset: n,
+                    length: 1,
+                },
+            };
+        }
+        window.push(f(&mut hare));
+        if hare == tortoise {
+            break;
+        }
+        if window.len() == power {
+            for quantity in window.drain(..) {
+                accumulate(&mut total, &quantity, 1);
+            }
+            tortoise = hare.clone();
+            checkpoint += power;
+            power *= 2;
+        }
+    }
+
+    // the hare is back at the checkpoint state, one cycle later
+    let cycle = Cycle {
+        offset: checkpoint,
+        length: window.len(),
+    };
+    let mut per_cycle = A::default();
+    for quantity in window.iter() {
+        accumulate(&mut per_cycle, quantity, 1);
+    }
+    let remaining = n - checkpoint;
+    accumulate(&mut total, &per_cycle, remaining / cycle.length);
+    for quantity in &window[..remaining % cycle.length] {
+        accumulate(&mut total, quantity, 1);
+    }
+    Extrapolation { total, cycle }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A permutation of 0..101 except for a detour for small values, so some
+    /// starting points have a tail before the cycle.
+    fn step(x: &u64) -> u64 {
+        (x * 2 + 1) % 101 + if *x < 5 { 50 } else { 0 }
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // sums the states visited along the way
+        let f = |x: &mut u64| {
+            *x = step(x);
+            *x
+        };
+        let add = |total: &mut u64, x: &u64, times: usize| *total += x * times as u64;
+        for n in [0, 1, 2, 5, 100, 1000] {
+            let mut x = 1;
+            let mut sum = 0;
+            for _ in 0..n {
+                x = step(&x);
+                sum += x;
+            }
+            assert_eq!(extrapolate(f, 1, n, add).total, sum);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Compass directions and turns. Offsets follow the grids, so `y` grows
+//! downwards and `Up` is `(0, -1)`.
+
+use super::Point2;
+
+/// One of the four axis-aligned directions, in clockwise order starting
+/// with `Right`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+/// A change of direction relative to the current heading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+    Around,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::Right, Self::Down, Self::Left, Self::Up];
+
+    fn from_index(i: u8) -> Self {
+        Self::ALL[(i % 4) as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self as u8 + 1)
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self as u8 + 3)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as u8 + 2)
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+            Turn::Around => self.reverse(),
+        }
+    }
+
+    pub fn offset(self) -> Point2 {
+        match self {
+            Self::Right => Point2::xy(1, 0),
+            Self::Down => Point2::xy(0, 1),
+            Self::Left => Point2::xy(-1, 0),
+            Self::Up => Point2::xy(0, -1),
+        }
+    }
+
+    /// The facing value used in passwords: 0 for right, 1 for down, 2 for
+    /// left and 3 for up.
+    pub fn facing(self) -> i64 {
+        self as i64
+    }
+
+    /// Parses `U/D/L/R`, `^/v/</>` and `N/S/W/E`.
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            b'R' | b'>' | b'E' => Some(Self::Right),
+            b'D' | b'v' | b'S' => Some(Self::Down),
+            b'L' | b'<' | b'W' => Some(Self::Left),
+            b'U' | b'^' | b'N' => Some(Self::Up),
+            _ => None,
+        }
+    }
+}
+
+impl Turn {
+    /// Parses `L` and `R`.
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            b'L' => Some(Self::Left),
+            b'R' => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for Point2 {
+    fn from(d: Direction) -> Self {
+        d.offset()
+    }
+}
+
+/// One of the eight compass directions, in clockwise order starting with
+/// `N`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    fn from_index(i: u8) -> Self {
+        Self::ALL[(i % 8) as usize]
+    }
+
+    /// Turns by 45 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self as u8 + 1)
+    }
+
+    /// Turns by 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self as u8 + 7)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as u8 + 4)
+    }
+
+    pub fn offset(self) -> Point2 {
+        let (x, y) = match self {
+            Self::N => (0, -1),
+            Self::NE => (1, -1),
+            Self::E => (1, 0),
+            Self::SE => (1, 1),
+            Self::S => (0, 1),
+            Self::SW => (-1, 1),
+            Self::W => (-1, 0),
+            Self::NW => (-1, -1),
+        };
+        Point2::xy(x, y)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::Right => Self::E,
+            Direction::Down => Self::S,
+            Direction::Left => Self::W,
+            Direction::Up => Self::N,
+        }
+    }
+}
+
+impl From<Direction8> for Point2 {
+    fn from(d: Direction8) -> Self {
+        d.offset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turn() {
+        use Direction::*;
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Right.turn_left(), Up);
+        assert_eq!(Left.reverse(), Right);
+        assert_eq!(Down.turn(Turn::Around).turn(Turn::Left), Left);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.offset() + d.reverse().offset(), Point2::default());
+            assert_eq!(Point2::from(Direction8::from(d)), d.offset());
+        }
+        for d in Direction8::ALL {
+            assert_eq!(d.turn_right().turn_right().turn_left().turn_left(), d);
+            assert_eq!(-d.offset(), d.reverse().offset());
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for (s, d) in [
+            ("UDLR", Direction::Up),
+            ("^v<>", Direction::Up),
+            ("NSWE", Direction::Up),
+        ] {
+            let parsed: Vec<_> = s.bytes().filter_map(Direction::from_byte).collect();
+            assert_eq!(
+                parsed,
+                vec![d, d.reverse(), Direction::Left, Direction::Right]
+            );
+        }
+        assert_eq!(Direction::from_byte(b'x'), None);
+        assert_eq!(Direction::Left.facing(), 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! Dense two-dimensional grids indexed by [`Point2`].
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use super::Point2;
+
+/// A row-major grid, `Point2::xy(0, 0)` is the top left cell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Width and height as signed coordinates.
+    pub fn dims(&self) -> (i64, i64) {
+        (self.width as i64, self.height as i64)
+    }
+
+    pub fn contains(&self, p: Point2) -> bool {
+        p.x() >= 0 && p.y() >= 0 && p.x() < self.width as i64 && p.y() < self.height as i64
+    }
+
+    fn offset(&self, p: Point2) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y() as usize * self.width + p.x() as usize)
+    }
+
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.cells[i])
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Point2> {
+        let (w, h) = self.dims();
+        (0..h).flat_map(move |y| (0..w).map(move |x| Point2::xy(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell matching `pred` in row-major order.
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Point2> {
+        self.iter().find(|(_, v)| pred(v)).map(|(p, _)| p)
+    }
+
+    /// Horizontal and vertical neighbors of `p` inside the grid.
+    pub fn neighbors4(&self, p: Point2) -> impl Iterator<Item = Point2> + '_ {
+        p.adjacent().filter(|&n| self.contains(n))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Parses one cell per byte and one row per line. Trailing `\r` and a
+    /// trailing empty line are dropped, shorter lines are padded with `pad`.
+    pub fn parse(s: &[u8], pad: u8) -> Self {
+        let mut lines: Vec<&[u8]> = s
+            .split(|x| *x == b'\n')
+            .map(|l| l.strip_suffix(b"\r").unwrap_or(l))
+            .collect();
+        if lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for l in lines.iter() {
+            cells.extend_from_slice(l);
+            cells.resize(cells.len() + width - l.len(), pad);
+        }
+        Self {
+            width,
+            height: lines.len(),
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        match self.offset(p) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "{p:?} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        match self.offset(p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "{p:?} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for x in row {
+                write!(f, "{x}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let g = Grid::parse(b"ab\r\nc\n", b' ');
+        assert_eq!(g.dims(), (2, 2));
+        assert_eq!(g.map(|&x| x as char).to_string(), "ab\nc \n");
+        assert_eq!(g.get(Point2::xy(1, 1)), Some(&b' '));
+        assert_eq!(g.get(Point2::xy(2, 0)), None);
+        assert_eq!(g.row(1), b"c ");
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let g = Grid::new(3, 3, 0);
+        assert_eq!(g.neighbors4(Point2::xy(0, 0)).count(), 2);
+        assert_eq!(g.neighbors4(Point2::xy(1, 1)).count(), 4);
+    }
+}
@@ -0,0 +1,187 @@
+//! A fast, non-cryptographic hasher for maps keyed by small integers and
+//! coordinates.
+//!
+//! The hash is the one used inside rustc (FxHash): every word is mixed in
+//! with a rotate, xor and multiply. It offers no protection against
+//! collision attacks, which puzzle inputs do not mount.
+//!
+//! Both hashers start from a seed that is zero unless [`set_seed`] changes
+//! it, so iteration order is the same on every run.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+pub type FastMap<K, V> = HashMap<K, V, FastState>;
+pub type FastSet<T> = HashSet<T, FastState>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+static SIPHASH: AtomicBool = AtomicBool::new(false);
+static HASH_SEED: AtomicU64 = AtomicU64::new(0);
+
+/// Makes maps created from now on hash with the standard library's SipHash
+/// instead, so that benchmarks can compare both.
+pub fn use_siphash(on: bool) {
+    SIPHASH.store(on, Ordering::Relaxed);
+}
+
+pub fn siphash_enabled() -> bool {
+    SIPHASH.load(Ordering::Relaxed)
+}
+
+/// Seeds the hashers of maps created from now on, which shuffles their
+/// iteration order.
+pub fn set_seed(seed: u64) {
+    HASH_SEED.store(seed, Ordering::Relaxed);
+}
+
+/// Creates [`FastHasher`]s, the choice of [`use_siphash`] and the seed are
+/// fixed when the state is created.
+#[derive(Clone, Copy, Debug)]
+pub struct FastState {
+    sip: bool,
+    seed: u64,
+}
+
+impl Default for FastState {
+    fn default() -> Self {
+        Self {
+            sip: siphash_enabled(),
+            seed: HASH_SEED.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl BuildHasher for FastState {
+    type Hasher = FastHasher;
+
+    fn build_hasher(&self) -> FastHasher {
+        if self.sip {
+            let mut sip = DefaultHasher::new();
+            if self.seed != 0 {
+                sip.write_u64(self.seed);
+            }
+            FastHasher::Sip(sip)
+        } else {
+            FastHasher::Fx(self.seed)
+        }
+    }
+}
+
+pub enum FastHasher {
+    Fx(u64),
+    Sip(DefaultHasher),
+}
+
+impl FastHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        match self {
+            FastHasher::Fx(hash) => *hash = (hash.rotate_left(5) ^ word).wrapping_mul(SEED),
+            FastHasher::Sip(sip) => sip.write_u64(word),
+        }
+    }
+}
+
+impl Hasher for FastHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        if let FastHasher::Sip(sip) = self {
+            return sip.write(bytes);
+        }
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0; 8];
+            buf[..rest.len()].copy_from_slice(rest);
+            // the length keeps "a" and "a\0" apart
+            self.add(u64::from_le_bytes(buf) ^ ((rest.len() as u64) << 56));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        match self {
+            // the multiply leaves the low bits poorly mixed, but hashbrown
+            // picks the bucket from them
+            FastHasher::Fx(hash) => hash.rotate_left(26),
+            FastHasher::Sip(sip) => sip.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::hash::Hash;
+
+    fn hash(x: impl Hash) -> u64 {
+        FastState {
+            sip: false,
+            seed: 0,
+        }
+        .hash_one(x)
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash((1i64, 2i64)), hash((1i64, 2i64)));
+        assert_ne!(hash((1i64, 2i64)), hash((2i64, 1i64)));
+        assert_ne!(hash(&b"a"[..]), hash(&b"a\0"[..]));
+        assert_ne!(hash(1u128), hash(1u128 << 64));
+        let mut sip = DefaultHasher::new();
+        5u64.hash(&mut sip);
+        let state = |sip, seed| FastState { sip, seed };
+        assert_eq!(state(true, 0).hash_one(5u64), sip.finish());
+        assert_ne!(state(true, 1).hash_one(5u64), sip.finish());
+        assert_ne!(state(false, 1).hash_one(5u64), hash(5u64));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut m = FastMap::default();
+        for i in 0..1000i64 {
+            m.insert((i, -i), i);
+        }
+        assert_eq!(m.len(), 1000);
+        assert_eq!(m[&(500, -500)], 500);
+        let s: FastSet<_> = (0..100u8).collect();
+        assert!(s.contains(&42));
+    }
+}
@@ -0,0 +1,256 @@
+//! Minimal PPM, PGM and animated GIF encoders.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Writes a binary PPM (P6) image, `rgb` holds three bytes per pixel.
+pub fn write_ppm(w: &mut impl Write, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    assert_eq!(rgb.len(), width * height * 3);
+    write!(w, "P6\n{} {}\n255\n", width, height)?;
+    w.write_all(rgb)
+}
+
+/// Writes a binary PGM (P5) image, one byte per pixel.
+pub fn write_pgm(w: &mut impl Write, width: usize, height: usize, gray: &[u8]) -> io::Result<()> {
+    assert_eq!(gray.len(), width * height);
+    write!(w, "P5\n{} {}\n255\n", width, height)?;
+    w.write_all(gray)
+}
+
+/// Index of the nearest color in the 6x6x6 color cube palette.
+fn palette_index(r: u8, g: u8, b: u8) -> u8 {
+    let q = |c: u8| (c as u16 * 5 + 127) / 255;
+    (q(r) * 36 + q(g) * 6 + q(b)) as u8
+}
+
+/// Packs variable width codes LSB first and splits them into GIF sub-blocks.
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        let mut blocks = Vec::with_capacity(self.bytes.len() + self.bytes.len() / 255 + 2);
+        for chunk in self.bytes.chunks(255) {
+            blocks.push(chunk.len() as u8);
+            blocks.extend_from_slice(chunk);
+        }
+        blocks.push(0);
+        blocks
+    }
+}
+
+/// GIF flavoured LZW compression of 8 bit indices.
+fn lzw(indices: &[u8]) -> Vec<u8> {
+    const MIN_SIZE: u32 = 8;
+    const CLEAR: u16 = 1 << MIN_SIZE;
+    const END: u16 = CLEAR + 1;
+    const MAX_CODE: u16 = 4096;
+
+    let mut out = BitWriter {
+        bytes: Vec::new(),
+        acc: 0,
+        bits: 0,
+    };
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = END + 1;
+    let mut size = MIN_SIZE + 1;
+    out.write(CLEAR, size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        out.write(END, size);
+        return out.finish();
+    };
+    let mut prefix = first as u16;
+    for &x in rest {
+        if let Some(&code) = dict.get(&(prefix, x)) {
+            prefix = code;
+            continue;
+        }
+        out.write(prefix, size);
+        // The decoder adds its entries one code later, so the code size
+        // grows once the next free code no longer fits.
+        if next >= 1 << size && size < 12 {
+            size += 1;
+        }
+        if next == MAX_CODE {
+            out.write(CLEAR, size);
+            dict.clear();
+            next = END + 1;
+            size = MIN_SIZE + 1;
+        } else {
+            dict.insert((prefix, x), next);
+            next += 1;
+        }
+        prefix = x as u16;
+    }
+    out.write(prefix, size);
+    if next >= 1 << size && size < 12 {
+        size += 1;
+    }
+    out.write(END, size);
+    out.finish()
+}
+
+/// Animated GIF with a fixed 6x6x6 color cube palette. Frames are compressed
+/// as they are added, the canvas is as large as the largest frame.
+pub struct Gif {
+    width: u16,
+    height: u16,
+    body: Vec<u8>,
+}
+
+impl Gif {
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            body: Vec::new(),
+        }
+    }
+
+    /// Adds a frame shown for `delay` hundredths of a second, `rgb` holds
+    /// three bytes per pixel.
+    pub fn add_frame(&mut self, width: usize, height: usize, rgb: &[u8], delay: u16) {
+        assert_eq!(rgb.len(), width * height * 3);
+        let (w, h) = (
+            width.min(u16::MAX as usize) as u16,
+            height.min(u16::MAX as usize) as u16,
+        );
+        self.width = self.width.max(w);
+        self.height = self.height.max(h);
+
+        // graphic control extension: restore to background after the frame
+        self.body.extend_from_slice(&[0x21, 0xf9, 4, 2 << 2]);
+        self.body.extend_from_slice(&delay.to_le_bytes());
+        self.body.extend_from_slice(&[0, 0]);
+        // image descriptor at (0, 0) without a local color table
+        self.body.extend_from_slice(&[0x2c, 0, 0, 0, 0]);
+        self.body.extend_from_slice(&w.to_le_bytes());
+        self.body.extend_from_slice(&h.to_le_bytes());
+        self.body.push(0);
+
+        let indices: Vec<u8> = rgb
+            .chunks(3)
+            .map(|p| palette_index(p[0], p[1], p[2]))
+            .collect();
+        self.body.push(8);
+        self.body.extend(lzw(&indices));
+    }
+
+    pub fn write(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(b"GIF89a")?;
+        w.write_all(&self.width.to_le_bytes())?;
+        w.write_all(&self.height.to_le_bytes())?;
+        // global color table with 256 entries, background color 0
+        w.write_all(&[0xf7, 0, 0])?;
+        for i in 0..=255u16 {
+            let c = |x: u16| (x * 51) as u8;
+            if i < 216 {
+                w.write_all(&[c(i / 36), c(i / 6 % 6), c(i % 6)])?;
+            } else {
+                w.write_all(&[0, 0, 0])?;
+            }
+        }
+        // loop forever
+        w.write_all(&[0x21, 0xff, 11])?;
+        w.write_all(b"NETSCAPE2.0")?;
+        w.write_all(&[3, 1, 0, 0, 0])?;
+        w.write_all(&self.body)?;
+        w.write_all(&[0x3b])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reference decoder following the GIF specification.
+    fn unlzw(blocks: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut i = 0;
+        while blocks[i] != 0 {
+            let len = blocks[i] as usize;
+            bytes.extend_from_slice(&blocks[i + 1..i + 1 + len]);
+            i += len + 1;
+        }
+
+        let (clear, end) = (256usize, 257usize);
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut size = 9;
+        let (mut acc, mut bits) = (0u32, 0);
+        let mut bytes = bytes.into_iter();
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        loop {
+            while bits < size {
+                acc |= (bytes.next().unwrap() as u32) << bits;
+                bits += 8;
+            }
+            let code = (acc & ((1 << size) - 1)) as usize;
+            acc >>= size;
+            bits -= size;
+            if code == clear {
+                table = (0..=255).map(|x| vec![x]).collect();
+                table.push(vec![]);
+                table.push(vec![]);
+                size = 9;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+            let entry = match (code < table.len(), &prev) {
+                (true, _) => table[code].clone(),
+                (false, Some(p)) => [&p[..], &p[..1]].concat(),
+                (false, None) => panic!("invalid code {code}"),
+            };
+            if let Some(p) = prev {
+                table.push([&p[..], &entry[..1]].concat());
+                if table.len() == 1 << size && size < 12 {
+                    size += 1;
+                }
+            }
+            out.extend_from_slice(&entry);
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw() {
+        let mut x = 12345u32;
+        let noisy: Vec<u8> = (0..100_000)
+            .map(|_| {
+                x = x.wrapping_mul(1103515245).wrapping_add(12345);
+                (x >> 16) as u8 % 7
+            })
+            .collect();
+        let runs: Vec<u8> = (0..50_000).map(|i| (i / 100 % 3) as u8).collect();
+        for data in [vec![], vec![42], runs, noisy] {
+            assert_eq!(unlzw(&lzw(&data)), data);
+        }
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = Vec::new();
+        write_ppm(&mut out, 2, 1, &[255, 0, 0, 0, 0, 255]).unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff");
+    }
+}
@@ -0,0 +1,146 @@
+//! Splitting puzzle inputs into lines, blank-line separated blocks and
+//! fixed-width columns.
+//!
+//! Lines may end in `\n` or `\r\n`, and a trailing newline at the end of the
+//! input does not produce an empty last line.
+
+use std::borrow::Cow;
+
+/// The first line of `s` without its line ending, and the rest after it.
+fn split_line(s: &[u8]) -> (&[u8], &[u8]) {
+    let (line, rest) = match s.iter().position(|&c| c == b'\n') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, &s[s.len()..]),
+    };
+    (line.strip_suffix(b"\r").unwrap_or(line), rest)
+}
+
+pub fn lines(s: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let (line, next) = split_line(rest);
+        rest = next;
+        Some(line)
+    })
+}
+
+/// Groups of lines separated by one or more empty lines. A block keeps the
+/// line endings between its lines but not the one after its last line.
+pub fn blocks(s: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        loop {
+            if rest.is_empty() {
+                return None;
+            }
+            let (line, next) = split_line(rest);
+            if !line.is_empty() {
+                break;
+            }
+            rest = next;
+        }
+        let start = rest;
+        let mut len = 0;
+        while !rest.is_empty() {
+            let (line, next) = split_line(rest);
+            if line.is_empty() {
+                break;
+            }
+            len = start.len() - rest.len() + line.len();
+            rest = next;
+        }
+        Some(&start[..len])
+    })
+}
+
+/// Splits `s` at its first empty line into the lines before and after it.
+/// Without an empty line all of `s` is the header.
+pub fn split_header(s: &[u8]) -> (&[u8], &[u8]) {
+    let mut rest = s;
+    while !rest.is_empty() {
+        let (line, next) = split_line(rest);
+        if line.is_empty() {
+            let header = &s[..s.len() - rest.len()];
+            let header = header.strip_suffix(b"\n").unwrap_or(header);
+            return (header.strip_suffix(b"\r").unwrap_or(header), next);
+        }
+        rest = next;
+    }
+    (s, &s[s.len()..])
+}
+
+/// Cuts `line` into fields of `width` bytes, the last one may be shorter.
+/// Useful for tables like the crate stacks of day 5.
+pub fn columns(line: &[u8], width: usize) -> impl Iterator<Item = &[u8]> {
+    line.chunks(width)
+}
+
+/// `s` with `\r\n` replaced by `\n`, for parsers that expect Unix line
+/// endings. Only allocates if there is something to replace.
+pub fn normalize(s: &[u8]) -> Cow<'_, [u8]> {
+    if !s.contains(&b'\r') {
+        return Cow::Borrowed(s);
+    }
+    let mut out = Vec::with_capacity(s.len());
+    for (i, &c) in s.iter().enumerate() {
+        if c != b'\r' || s.get(i + 1) != Some(&b'\n') {
+            out.push(c);
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn collect<'a>(it: impl Iterator<Item = &'a [u8]>) -> Vec<&'a str> {
+        it.map(|x| std::str::from_utf8(x).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(collect(lines(b"a\nb\r\n\nc")), vec!["a", "b", "", "c"]);
+        assert_eq!(collect(lines(b"a\r\nb\r\n")), vec!["a", "b"]);
+        assert_eq!(collect(lines(b"a\n\n")), vec!["a", ""]);
+        assert_eq!(lines(b"").count(), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        for s in [
+            &b"1\n2\n\n3\n"[..],
+            b"\n1\n2\n\n\n3",
+            b"1\r\n2\r\n\r\n3\r\n\r\n",
+        ] {
+            let blocks = blocks(s).collect::<Vec<_>>();
+            assert_eq!(blocks.len(), 2);
+            assert_eq!(collect(lines(blocks[0])), vec!["1", "2"]);
+            assert_eq!(collect(lines(blocks[1])), vec!["3"]);
+        }
+        assert_eq!(blocks(b"\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_split_header() {
+        assert_eq!(
+            split_header(b"a\r\nb\r\n\r\nc\r\n"),
+            (&b"a\r\nb"[..], &b"c\r\n"[..])
+        );
+        assert_eq!(split_header(b"a\n\n"), (&b"a"[..], &b""[..]));
+        assert_eq!(split_header(b"a\nb"), (&b"a\nb"[..], &b""[..]));
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(
+            collect(columns(b"[A]     [C]", 4)),
+            vec!["[A] ", "    ", "[C]"]
+        );
+        assert_eq!(&*normalize(b"a\r\nb\rc\n"), b"a\nb\rc\n");
+        assert!(matches!(normalize(b"a\nb"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,209 @@
+//! Sets of integers stored as sorted, disjoint inclusive ranges.
+
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+/// A set of integers made of inclusive ranges. Overlapping and adjacent
+/// ranges are coalesced on insertion, so `0..=3` and `4..=5` become `0..=5`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    /// Sorted, neither overlapping nor adjacent.
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// The disjoint ranges in ascending order.
+    #[cfg(test)]
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(a, b)| a..=b)
+    }
+
+    /// Number of integers in the set.
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, &(a, b)| acc + (b - a) + T::one())
+    }
+
+    /// Whether all integers of `range` are in the set, true for an empty
+    /// range.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        let i = self.ranges.partition_point(|r| r.1 < start);
+        start > end
+            || self
+                .ranges
+                .get(i)
+                .is_some_and(|r| r.0 <= start && end <= r.1)
+    }
+
+    /// Adds all integers of `range`, an empty range is ignored.
+    #[inline]
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // ranges[i..j] overlap or touch the new one
+        let i = self
+            .ranges
+            .partition_point(|r| r.1.saturating_add(T::one()) < start);
+        // appending and inserting a range that is already covered are cheap
+        match self.ranges.get(i) {
+            None => {
+                self.ranges.push((start, end));
+                return;
+            }
+            Some(r) if r.0 <= start && end <= r.1 => return,
+            _ => (),
+        }
+        let j = i + self.ranges[i..].partition_point(|r| r.0 <= end.saturating_add(T::one()));
+        if i == j {
+            self.ranges.insert(i, (start, end));
+            return;
+        }
+        start = start.min(self.ranges[i].0);
+        end = end.max(self.ranges[j - 1].1);
+        self.ranges[i] = (start, end);
+        if j > i + 1 {
+            self.ranges.drain(i + 1..j);
+        }
+    }
+
+    /// The integers in `bounds` that are not in the set as ranges in
+    /// ascending order.
+    pub fn gaps_in(
+        &self,
+        bounds: RangeInclusive<T>,
+    ) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let (lo, hi) = bounds.into_inner();
+        // the first integer that may still be part of a gap
+        let mut cursor = Some(lo);
+        let mut i = self.ranges.partition_point(|r| r.1 < lo);
+        std::iter::from_fn(move || loop {
+            let c = cursor.filter(|&c| c <= hi)?;
+            match self.ranges.get(i) {
+                Some(&(a, b)) if a <= hi => {
+                    i += 1;
+                    cursor = b.checked_add(&T::one());
+                    if a > c {
+                        return Some(c..=a - T::one());
+                    }
+                }
+                _ => {
+                    cursor = None;
+                    return Some(c..=hi);
+                }
+            }
+        })
+    }
+}
+
+/// Containment and overlap of single ranges, without building an
+/// [`IntervalSet`].
+pub trait RangeExt<T> {
+    fn contains_range(&self, other: &Self) -> bool;
+    fn overlaps(&self, other: &Self) -> bool;
+}
+
+impl<T: PrimInt> RangeExt<T> for RangeInclusive<T> {
+    fn contains_range(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start() <= other.start() && other.end() <= self.end())
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.start() <= other.end()
+            && other.start() <= self.end()
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for r in iter {
+            set.insert(r);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(ranges: &[RangeInclusive<i64>]) -> IntervalSet<i64> {
+        ranges.iter().cloned().collect()
+    }
+
+    fn ranges(s: &IntervalSet<i64>) -> Vec<RangeInclusive<i64>> {
+        s.ranges().collect()
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_insert() {
+        for (a, b, merged) in [
+            (0..=3, 4..=5, vec![0..=5]),
+            (0..=4, 4..=5, vec![0..=5]),
+            (0..=5, 4..=5, vec![0..=5]),
+            (0..=6, 1..=3, vec![0..=6]),
+            (0..=3, 5..=6, vec![0..=3, 5..=6]),
+            (6..=0, 4..=5, vec![4..=5]),
+        ] {
+            assert_eq!(ranges(&set(&[a.clone(), b.clone()])), merged);
+            assert_eq!(ranges(&set(&[b, a])), merged);
+        }
+        let s = set(&[10..=12, 0..=1, 5..=6, 3..=3, 2..=2]);
+        assert_eq!(ranges(&s), vec![0..=3, 5..=6, 10..=12]);
+        assert_eq!(s.total_len(), 9);
+        assert!(s.contains_range(3..=3) && !s.contains_range(4..=4));
+        assert_eq!(s.gaps_in(0..=12).collect::<Vec<_>>(), vec![4..=4, 7..=9]);
+        assert_eq!(ranges(&set(&[0..=3, 5..=6, 2..=8])), vec![0..=8]);
+        assert_eq!(
+            ranges(&set(&[i64::MIN..=0, 1..=i64::MAX])),
+            vec![i64::MIN..=i64::MAX]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_gaps() {
+        let a = set(&[0..=5, 10..=15]);
+        let gaps = |bounds| a.gaps_in(bounds).collect::<Vec<_>>();
+        assert_eq!(gaps(-2..=12), vec![-2..=-1, 6..=9]);
+        assert_eq!(gaps(6..=9), vec![6..=9]);
+        assert_eq!(gaps(20..=22), vec![20..=22]);
+        assert!(gaps(1..=4).is_empty());
+        assert!(gaps(5..=4).is_empty());
+        assert_eq!(a.gaps_in(i64::MIN..=i64::MAX).count(), 3);
+        assert_eq!(
+            set(&[0..=i64::MAX])
+                .gaps_in(-1..=i64::MAX)
+                .collect::<Vec<_>>(),
+            vec![-1..=-1]
+        );
+        assert!(a.contains_range(11..=15) && a.contains_range(7..=6));
+        assert!(!a.contains_range(4..=10) && !a.contains_range(16..=16));
+        assert!((0..=5).contains_range(&(1..=5)) && !(0..=5).contains_range(&(1..=6)));
+        assert!((0..=5).overlaps(&(5..=6)) && !(0..=5).overlaps(&(6..=7)));
+    }
+}
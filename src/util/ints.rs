@@ -0,0 +1,172 @@
+//! Overflow-checked parsing of decimal integers of any width.
+//!
+//! Digits are scanned and converted eight at a time by treating them as the
+//! bytes of a `u64` (SWAR, SIMD within a register).
+
+use nom::error::{Error, ErrorKind};
+use nom::IResult;
+use num::PrimInt;
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+
+/// Reads up to eight bytes little endian, missing bytes are zero.
+fn load(s: &[u8]) -> u64 {
+    if let Some(chunk) = s.first_chunk::<8>() {
+        return u64::from_le_bytes(*chunk);
+    }
+    let mut buf = [0; 8];
+    buf[..s.len()].copy_from_slice(s);
+    u64::from_le_bytes(buf)
+}
+
+/// Number of leading ASCII digits in `word`.
+fn digit_run(word: u64) -> usize {
+    // digits become 0..=9, everything else 10 or more
+    let t = word ^ (ONES * b'0' as u64);
+    // sets the high bit of every byte above 9; a carry out of such a byte
+    // only disturbs the bytes after it
+    let flags = (t.wrapping_add(ONES * 0x76) | t) & (ONES * 0x80);
+    flags.trailing_zeros() as usize / 8
+}
+
+/// Value of the first `n` digits of `word`, `0 < n <= 8`.
+fn digits_value(word: u64, n: usize) -> u64 {
+    let v = word.wrapping_sub(ONES * b'0' as u64);
+    // move the digits to the top, the bytes below become leading zeros
+    let mut v = v << (64 - 8 * n);
+    v = (v * 10 + (v >> 8)) & 0x00ff_00ff_00ff_00ff;
+    v = (v * 100 + (v >> 16)) & 0x0000_ffff_0000_ffff;
+    (v * 10000 + (v >> 32)) & 0x0000_0000_ffff_ffff
+}
+
+/// Parses a decimal integer at the start of `s`, with a leading `-` for
+/// signed types. Returns the value and the rest of the input, or `None` if
+/// there are no digits or the value does not fit into `T`.
+pub fn parse<T: PrimInt>(s: &[u8]) -> Option<(T, &[u8])> {
+    let negative = T::min_value() < T::zero() && s.first() == Some(&b'-');
+    let mut i = negative as usize;
+    let mut acc = T::zero();
+    loop {
+        let word = load(&s[i..]);
+        let n = digit_run(word);
+        if n == 0 {
+            break;
+        }
+        // negative numbers are accumulated downwards to reach `T::MIN`
+        let v = digits_value(word, n);
+        let v = if negative {
+            T::from(-(v as i128))?
+        } else {
+            T::from(v)?
+        };
+        if !acc.is_zero() {
+            acc = acc.checked_mul(&T::from(10u64.pow(n as u32))?)?;
+        }
+        acc = acc.checked_add(&v)?;
+        i += n;
+        if n < 8 {
+            break;
+        }
+    }
+    if i == negative as usize {
+        return None;
+    }
+    Some((acc, &s[i..]))
+}
+
+fn is_signed<T: PrimInt>() -> bool {
+    T::min_value() < T::zero()
+}
+
+/// [`parse`] as a nom parser, failing with [`ErrorKind::TooLarge`] on
+/// overflow.
+pub fn int<T: PrimInt>(i: &[u8]) -> IResult<&[u8], T> {
+    match parse(i) {
+        Some((v, rest)) => Ok((rest, v)),
+        None => {
+            let digits = match i.strip_prefix(b"-") {
+                Some(digits) if is_signed::<T>() => digits,
+                _ => i,
+            };
+            let kind = if digits.first().is_some_and(u8::is_ascii_digit) {
+                ErrorKind::TooLarge
+            } else {
+                ErrorKind::Digit
+            };
+            Err(nom::Err::Error(Error::new(i, kind)))
+        }
+    }
+}
+
+/// All integers in free-form text such as `x=-3, y=12`. A `-` right before
+/// the digits makes the number negative for signed types. Numbers that do
+/// not fit into `T` are skipped.
+pub fn extract_ints<T: PrimInt>(s: &[u8]) -> impl Iterator<Item = T> + '_ {
+    let mut rest = s;
+    std::iter::from_fn(move || loop {
+        let start = rest.iter().position(u8::is_ascii_digit)?;
+        let sign = (is_signed::<T>() && start > 0 && rest[start - 1] == b'-') as usize;
+        if let Some((v, next)) = parse(&rest[start - sign..]) {
+            rest = next;
+            return Some(v);
+        }
+        let digits = rest[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        rest = &rest[start + digits..];
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse::<i64>(b"123abc"), Some((123, &b"abc"[..])));
+        assert_eq!(parse::<i64>(b"-42"), Some((-42, &b""[..])));
+        assert_eq!(parse::<u32>(b"-42"), None);
+        assert_eq!(parse::<i64>(b"-"), None);
+        assert_eq!(parse::<i64>(b"x1"), None);
+        assert_eq!(parse::<u8>(b"255"), Some((255, &b""[..])));
+        assert_eq!(parse::<u8>(b"256"), None);
+        assert_eq!(parse::<u8>(b"000000000255"), Some((255, &b""[..])));
+        assert_eq!(parse::<i8>(b"-128"), Some((-128, &b""[..])));
+        assert_eq!(parse::<i8>(b"128"), None);
+        for x in [0, 7, 12345678, 123456789, u64::MAX / 3, u64::MAX] {
+            let s = format!("{x},");
+            assert_eq!(parse::<u64>(s.as_bytes()), Some((x, &b","[..])));
+        }
+        assert_eq!(parse::<u64>(b"18446744073709551616"), None);
+        for x in [i128::MIN, i128::MAX, -1] {
+            assert_eq!(parse::<i128>(x.to_string().as_bytes()).unwrap().0, x);
+        }
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int::<i64>(b"-7 x"), Ok((&b" x"[..], -7)));
+        let kind = |r: IResult<&[u8], u8>| match r {
+            Err(nom::Err::Error(e)) => Some(e.code),
+            _ => None,
+        };
+        assert_eq!(kind(int(b"300")), Some(ErrorKind::TooLarge));
+        assert_eq!(kind(int(b"-3")), Some(ErrorKind::Digit));
+        assert_eq!(kind(int(b"")), Some(ErrorKind::Digit));
+    }
+
+    #[test]
+    fn test_extract() {
+        let line = b"Sensor at x=-2, y=15: closest beacon is at x=3-4, y=99999999999";
+        assert_eq!(
+            extract_ints::<i64>(line).collect::<Vec<_>>(),
+            vec![-2, 15, 3, -4, 99999999999]
+        );
+        assert_eq!(
+            extract_ints::<u32>(line).collect::<Vec<_>>(),
+            vec![2, 15, 3, 4]
+        );
+        assert_eq!(extract_ints::<i64>(b"- -").count(), 0);
+    }
+}
@@ -0,0 +1,157 @@
+//! Memoized recursion over states packed into compact integer keys.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use super::hash::FastMap;
+
+/// Appends fixed-width fields to a 128 bit key, most significant first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Packer {
+    key: u128,
+    bits: u32,
+}
+
+impl Packer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `value` using `bits` bits. Panics if the value is negative,
+    /// does not fit or the key would grow beyond 128 bits.
+    pub fn push(mut self, value: impl TryInto<u64> + Copy + Debug, bits: u32) -> Self {
+        let v = match value.try_into() {
+            Ok(v) if bits <= 64 && (v as u128) < 1 << bits => v,
+            _ => overflow(value, bits),
+        };
+        self.bits += bits;
+        if self.bits > 128 {
+            overflow(value, bits);
+        }
+        self.key = self.key << bits | v as u128;
+        self
+    }
+
+    pub fn finish(self) -> u128 {
+        self.key
+    }
+}
+
+#[cold]
+fn overflow(value: impl Debug, bits: u32) -> ! {
+    panic!("{value:?} does not fit into {bits} bits of a 128 bit key")
+}
+
+/// States that can be packed into a key, so that equal states give equal
+/// keys and different states different keys.
+pub trait Pack {
+    fn pack(&self) -> u128;
+}
+
+/// Cache sizes and hit rates, shown by the tracing layer.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub entries: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    pub fn hit_rate(&self) -> f64 {
+        self.hits as f64 / (self.hits + self.misses).max(1) as f64
+    }
+}
+
+impl Debug for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} entries, {} hits, {} misses ({:.1}% hit rate)",
+            self.entries,
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// A cache for recursive functions, see [`Memo::get_or_insert_with`].
+pub struct Memo<K, V> {
+    cache: FastMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: FastMap::default(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached value for `key`, or the result of `f`, which gets the memo
+    /// back so that it can recurse through it.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            self.hits += 1;
+            return v.clone();
+        }
+        self.misses += 1;
+        let v = f(self);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            entries: self.cache.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+impl<V: Clone> Memo<u128, V> {
+    /// [`Memo::get_or_insert_with`] keyed by the packed `state`.
+    pub fn get_packed(&mut self, state: &impl Pack, f: impl FnOnce(&mut Self) -> V) -> V {
+        self.get_or_insert_with(state.pack(), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pack() {
+        let key = Packer::new()
+            .push(3u8, 2)
+            .push(0, 0)
+            .push(5i64, 4)
+            .push(u64::MAX, 64)
+            .finish();
+        assert_eq!(key, (3 << 68) | (5 << 64) | u64::MAX as u128);
+        assert!(std::panic::catch_unwind(|| Packer::new().push(4, 2)).is_err());
+        assert!(std::panic::catch_unwind(|| Packer::new().push(-1i64, 8)).is_err());
+    }
+
+    #[test]
+    fn test_memo() {
+        fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+            if n < 2 {
+                return n;
+            }
+            memo.get_or_insert_with(n, |memo| fib(memo, n - 1) + fib(memo, n - 2))
+        }
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+        let stats = memo.stats();
+        assert_eq!((stats.entries, stats.misses, stats.hits), (89, 89, 87));
+        assert_eq!(
+            format!("{stats:?}"),
+            "89 entries, 87 hits, 89 misses (49.4% hit rate)"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use crate::error::Result;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub mod arena;
+pub mod cycle;
+mod direction;
+mod grid;
+pub mod hash;
+pub mod image;
+pub mod input;
+mod interval;
+pub mod ints;
+pub mod memo;
+pub mod num;
+mod point;
+pub mod search;
+mod sparse;
+
+pub use direction::{Direction, Direction8, Turn};
+pub use grid::Grid;
+pub use interval::{IntervalSet, RangeExt};
+pub use point::{Point2, Point3};
+pub use sparse::SparseGrid;
+
+lazy_static! {
+    /// Input files served from memory instead of the file system, see [`preload`].
+    static ref PRELOADED: RwLock<HashMap<PathBuf, Vec<u8>>> = RwLock::new(HashMap::new());
+}
+
+/// Serves `data` for all reads of `path` through [`read_bytes`] until
+/// [`unload`] is called.
+pub fn preload(path: impl Into<PathBuf>, data: Vec<u8>) {
+    PRELOADED.write().unwrap().insert(path.into(), data);
+}
+
+pub fn unload(path: impl AsRef<Path>) {
+    PRELOADED.write().unwrap().remove(path.as_ref());
+}
+
+/// Path of the puzzle input of a day.
+pub fn input_path(day_no: usize) -> String {
+    format!("inputs/day{:02}a", day_no)
+}
+
+/// Preloads every `inputs/dayNN*` file of a day.
+pub fn preload_day(day_no: usize) -> Result<()> {
+    let prefix = format!("day{:02}", day_no);
+    for entry in fs::read_dir("inputs")? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .file_name()
+                .is_some_and(|x| x.to_string_lossy().starts_with(&prefix))
+        {
+            let data = fs::read(&path)?;
+            preload(path, data);
+        }
+    }
+    Ok(())
+}
+
+pub fn read_bytes(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    if let Some(data) = PRELOADED.read().unwrap().get(path.as_ref()) {
+        return Ok(data.clone());
+    }
+    Ok(fs::read(path)?)
+}
+
+pub fn read_string(path: impl AsRef<Path>) -> Result<String> {
+    Ok(String::from_utf8(read_bytes(path)?)?)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    if d.as_secs() > 0 {
+        format!("{:.02}s ", d.as_millis() as f64 / 1000.0)
+    } else if d.as_millis() > 0 {
+        format!("{:.02}ms", d.as_nanos() as f64 / 1000.0 / 1000.0)
+    } else {
+        format!("{:.02}µs", d.as_nanos() as f64 / 1000.0)
+    }
+}
+
+/// Inverse of [`format_duration`].
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (value, unit) = s.split_at(s.find(|c: char| c.is_alphabetic() || c == 'µ')?);
+    let value: f64 = value.parse().ok()?;
+    let secs = match unit {
+        "s" => value,
+        "ms" => value / 1e3,
+        "µs" => value / 1e6,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(secs))
+}
+
+pub fn format_size(bytes: i64) -> String {
+    if bytes >= 1 << 30 {
+        format!("{:.02}GiB", bytes as f64 / (1 << 30) as f64)
+    } else if bytes >= 1 << 20 {
+        format!("{:.02}MiB", bytes as f64 / (1 << 20) as f64)
+    } else {
+        format!("{:.02}KiB", bytes as f64 / (1 << 10) as f64)
+    }
+}
+
+/// Removes `name <value>` from `args` and returns the value, or an error if
+/// `name` is the last argument.
+pub fn take_opt(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    let Some(i) = args.iter().position(|x| x == name) else {
+        return Ok(None);
+    };
+    args.remove(i);
+    if i < args.len() {
+        Ok(Some(args.remove(i)))
+    } else {
+        Err(format!("missing value for {name}").into())
+    }
+}
+
+/// Removes `name` from `args` and returns whether it was present.
+pub fn take_switch(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|x| x == name) {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_take_opt() {
+        let mut args: Vec<String> = ["15", "--runs", "3", "--tag"].map(String::from).into();
+        assert_eq!(
+            take_opt(&mut args, "--runs").unwrap(),
+            Some("3".to_string())
+        );
+        assert_eq!(take_opt(&mut args, "--runs").unwrap(), None);
+        let err = take_opt(&mut args, "--tag").unwrap_err();
+        assert_eq!(err.to_string(), "missing value for --tag");
+        assert_eq!(args, ["15"]);
+    }
+}
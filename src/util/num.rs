@@ -0,0 +1,91 @@
+//! Number theory over any primitive integer type.
+
+use std::cmp::min;
+use std::mem::swap;
+
+use num::PrimInt;
+
+fn abs<T: PrimInt>(x: T) -> T {
+    if x < T::zero() {
+        T::zero() - x
+    } else {
+        x
+    }
+}
+
+/// Binary GCD, the result is never negative.
+pub fn gcd<T: PrimInt>(u: T, v: T) -> T {
+    let (mut u, mut v) = (abs(u), abs(v));
+
+    // Base cases: gcd(n, 0) = gcd(0, n) = n
+    if u.is_zero() {
+        return v;
+    } else if v.is_zero() {
+        return u;
+    }
+
+    // Using identities 2 and 3:
+    // gcd(2ⁱ u, 2ʲ v) = 2ᵏ gcd(u, v) with u, v odd and k = min(i, j)
+    // 2ᵏ is the greatest power of two that divides both u and v
+    let i = u.trailing_zeros() as usize;
+    u = u >> i;
+    let j = v.trailing_zeros() as usize;
+    v = v >> j;
+    let k = min(i, j);
+
+    loop {
+        // u and v are odd at the start of the loop
+        debug_assert!(u & T::one() == T::one(), "u is even");
+        debug_assert!(v & T::one() == T::one(), "v is even");
+
+        // Swap if necessary so u <= v
+        if u > v {
+            swap(&mut u, &mut v);
+        }
+        // u and v are still both odd after (potentially) swapping
+
+        // Using identity 4 (gcd(u, v) = gcd(|v-u|, min(u, v))
+        v = v - u;
+        // v is now even, but u is unchanged (and odd)
+
+        // Identity 1: gcd(u, 0) = u
+        // The shift by k is necessary to add back the 2ᵏ factor that was removed before the loop
+        if v.is_zero() {
+            return u << k;
+        }
+
+        // Identity 3: gcd(u, 2ʲ v) = gcd(u, v) (u is known to be odd)
+        v = v >> v.trailing_zeros() as usize;
+        // v is now odd again
+    }
+}
+
+/// Least common multiple, zero if either argument is zero.
+pub fn lcm<T: PrimInt>(a: T, b: T) -> T {
+    if a.is_zero() || b.is_zero() {
+        return T::zero();
+    }
+    abs(a / gcd(a, b) * b)
+}
+
+/// [`lcm`] of all `xs`, one for none.
+pub fn lcm_all<T: PrimInt>(xs: impl IntoIterator<Item = T>) -> T {
+    xs.into_iter().fold(T::one(), lcm)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u8, 7), 7);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(gcd(u128::MAX, 5), 5);
+        assert_eq!(lcm(4i64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm_all([2, 3, 5, 7, 11, 13, 17, 19]), 9699690);
+        assert_eq!(lcm_all::<i64>([]), 1);
+    }
+}
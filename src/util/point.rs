@@ -0,0 +1,280 @@
+//! Fixed size points with component-wise arithmetic.
+
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+use num::{NumCast, Signed, Zero};
+
+/// A point or offset with `N` coordinates, ordered lexicographically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+pub type Point2<T = i64> = Point<T, 2>;
+pub type Point3<T = i64> = Point<T, 3>;
+
+impl<T, const N: usize> Point<T, N> {
+    pub const fn new(coords: [T; N]) -> Self {
+        Self(coords)
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Point<U, N> {
+        Point(self.0.map(f))
+    }
+}
+
+impl<T: Default + Copy, const N: usize> Default for Point<T, N> {
+    fn default() -> Self {
+        Self([T::default(); N])
+    }
+}
+
+impl<T: Copy, const N: usize> Point<T, N> {
+    /// Converts every coordinate to `U`, `None` if one does not fit.
+    pub fn cast<U: NumCast + Zero + Copy>(self) -> Option<Point<U, N>>
+    where
+        T: NumCast,
+    {
+        let mut out = [U::zero(); N];
+        for (o, x) in out.iter_mut().zip(self.0) {
+            *o = U::from(x)?;
+        }
+        Some(Point(out))
+    }
+
+    fn zip_with(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        let mut out = self.0;
+        for (o, x) in out.iter_mut().zip(other.0) {
+            *o = f(*o, x);
+        }
+        Self(out)
+    }
+}
+
+impl<T: Copy> Point<T, 2> {
+    pub const fn xy(x: T, y: T) -> Self {
+        Self([x, y])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy> Point<T, 3> {
+    pub const fn xyz(x: T, y: T, z: T) -> Self {
+        Self([x, y, z])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T: Signed + PartialOrd + Copy, const N: usize> Point<T, N> {
+    pub fn abs(self) -> Self {
+        self.map(|x| x.abs())
+    }
+
+    pub fn signum(self) -> Self {
+        self.map(|x| x.signum())
+    }
+
+    /// Taxicab distance, the sum of the coordinate differences.
+    pub fn manhattan(self, other: Self) -> T {
+        (self - other)
+            .0
+            .iter()
+            .fold(T::zero(), |acc, x| acc + x.abs())
+    }
+
+    /// Chessboard distance, the largest coordinate difference.
+    pub fn chebyshev(self, other: Self) -> T {
+        (self - other).0.iter().fold(
+            T::zero(),
+            |acc, x| if x.abs() > acc { x.abs() } else { acc },
+        )
+    }
+
+    /// The `2 * N` unit offsets along a single axis, negative one first.
+    pub fn unit_offsets() -> impl Iterator<Item = Self> {
+        (0..N).flat_map(|axis| {
+            [-T::one(), T::one()].map(|d| {
+                let mut p = [T::zero(); N];
+                p[axis] = d;
+                Self(p)
+            })
+        })
+    }
+
+    /// All `3^N - 1` offsets with coordinates in `-1..=1`, including the
+    /// diagonals, in lexicographic order.
+    pub fn all_offsets() -> impl Iterator<Item = Self> {
+        (0..3usize.pow(N as u32)).filter_map(|mut i| {
+            let mut p = [T::zero(); N];
+            for x in p.iter_mut().rev() {
+                *x = match i % 3 {
+                    0 => -T::one(),
+                    1 => T::zero(),
+                    _ => T::one(),
+                };
+                i /= 3;
+            }
+            Some(Self(p)).filter(|p| p.0.iter().any(|x| !x.is_zero()))
+        })
+    }
+
+    /// The points next to this one along a single axis.
+    pub fn adjacent(self) -> impl Iterator<Item = Self> {
+        Self::unit_offsets().map(move |d| self + d)
+    }
+
+    /// The points next to this one, including the diagonals.
+    pub fn surrounding(self) -> impl Iterator<Item = Self> {
+        Self::all_offsets().map(move |d| self + d)
+    }
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> Add for Point<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<T: Sub<Output = T> + Copy, const N: usize> Sub for Point<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+/// Scales every coordinate.
+impl<T: Mul<Output = T> + Copy, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        self.map(|x| x * rhs)
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Self(coords)
+    }
+}
+
+impl<T, const N: usize> From<Point<T, N>> for [T; N] {
+    fn from(p: Point<T, N>) -> Self {
+        p.0
+    }
+}
+
+impl<T> From<(T, T)> for Point<T, 2> {
+    fn from((x, y): (T, T)) -> Self {
+        Self([x, y])
+    }
+}
+
+impl<T> From<Point<T, 2>> for (T, T) {
+    fn from(Point([x, y]): Point<T, 2>) -> Self {
+        (x, y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point<T, 3> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self([x, y, z])
+    }
+}
+
+impl<T> From<Point<T, 3>> for (T, T, T) {
+    fn from(Point([x, y, z]): Point<T, 3>) -> Self {
+        (x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        let a = Point2::xy(1, -2);
+        let b = Point2::from((4, 2));
+        assert_eq!(a + b, Point2::xy(5, 0));
+        assert_eq!(b - a, Point2::xy(3, 4));
+        assert_eq!(-a * 3, Point2::xy(-3, 6));
+        assert_eq!((b - a).signum(), Point2::xy(1, 1));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(
+            Point3::xyz(1i8, 2, 3).cast::<u8>(),
+            Some(Point3::xyz(1, 2, 3))
+        );
+        assert_eq!(Point3::xyz(-1i8, 2, 3).cast::<u8>(), None);
+        assert_eq!(<(i64, i64)>::from(a), (1, -2));
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Point2::<i64>::unit_offsets().count(), 4);
+        assert_eq!(Point2::<i64>::all_offsets().count(), 8);
+        assert_eq!(Point3::<i8>::unit_offsets().count(), 6);
+        assert_eq!(Point3::<i8>::all_offsets().count(), 26);
+        assert!(Point3::<i8>::unit_offsets().all(|d| d.manhattan(Point::default()) == 1));
+        assert!(Point2::xy(3, 3)
+            .surrounding()
+            .all(|p| p.chebyshev(Point2::xy(3, 3)) == 1));
+        assert_eq!(
+            Point2::<i64>::all_offsets().next(),
+            Some(Point2::xy(-1, -1))
+        );
+    }
+}
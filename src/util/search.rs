@@ -0,0 +1,289 @@
+//! Breadth-first, Dijkstra and A* search over implicit graphs.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+
+use super::hash::FastMap;
+
+/// An implicit graph to search, the states are generated on the fly.
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+
+    /// Pushes the successors of `state` with the cost of getting there to
+    /// `out`. Costs must not be negative.
+    fn neighbors(&mut self, state: &Self::State, out: &mut Vec<(Self::State, i64)>);
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Lower bound of the remaining cost to a goal, used by [`astar`].
+    fn heuristic(&self, _state: &Self::State) -> i64 {
+        0
+    }
+
+    /// Called before `state` is expanded, with the search so far and the
+    /// states still waiting in the frontier.
+    fn visit(
+        &mut self,
+        _state: &Self::State,
+        _explored: &Explored<Self::State>,
+        _frontier: &mut dyn Iterator<Item = &Self::State>,
+    ) {
+    }
+}
+
+/// Counters describing the work done by a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States taken from the frontier and expanded.
+    pub expanded: usize,
+    /// Distinct states that were reached.
+    pub visited: usize,
+    /// Largest number of entries in the frontier at once.
+    pub max_frontier: usize,
+}
+
+struct Node<S> {
+    state: S,
+    cost: i64,
+    parent: Option<usize>,
+}
+
+/// Everything a search has reached, with the cheapest known cost and the
+/// predecessor of each state.
+pub struct Explored<S> {
+    nodes: Vec<Node<S>>,
+    index: FastMap<S, usize>,
+    goal: Option<usize>,
+    pub stats: Stats,
+}
+
+impl<S: Clone + Eq + Hash> Explored<S> {
+    fn new(start: S) -> Self {
+        Self {
+            nodes: vec![Node {
+                state: start.clone(),
+                cost: 0,
+                parent: None,
+            }],
+            index: FastMap::from_iter([(start, 0)]),
+            goal: None,
+            stats: Stats {
+                visited: 1,
+                ..Stats::default()
+            },
+        }
+    }
+
+    /// Records `state` if it is new or cheaper than before, returns its
+    /// index in that case.
+    fn relax(&mut self, state: S, cost: i64, parent: usize) -> Option<usize> {
+        match self.index.get(&state) {
+            Some(&i) if self.nodes[i].cost <= cost => None,
+            Some(&i) => {
+                self.nodes[i].cost = cost;
+                self.nodes[i].parent = Some(parent);
+                Some(i)
+            }
+            None => {
+                let i = self.nodes.len();
+                self.index.insert(state.clone(), i);
+                self.nodes.push(Node {
+                    state,
+                    cost,
+                    parent: Some(parent),
+                });
+                self.stats.visited += 1;
+                Some(i)
+            }
+        }
+    }
+
+    /// Cost of the cheapest path to the goal, `None` if none was found.
+    pub fn cost(&self) -> Option<i64> {
+        self.goal.map(|i| self.nodes[i].cost)
+    }
+
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.map(|i| &self.nodes[i].state)
+    }
+
+    /// The states from the start to the goal, both included.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.map(|i| self.path_from(i))
+    }
+
+    /// Cheapest known cost of reaching `state`.
+    pub fn cost_to(&self, state: &S) -> Option<i64> {
+        self.index.get(state).map(|&i| self.nodes[i].cost)
+    }
+
+    fn path_from(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.nodes[i].state.clone()];
+        while let Some(p) = self.nodes[i].parent {
+            path.push(self.nodes[p].state.clone());
+            i = p;
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn contains(&self, state: &S) -> bool {
+        self.index.contains_key(state)
+    }
+
+    /// All states reached so far with their cost.
+    pub fn reached(&self) -> impl Iterator<Item = (&S, i64)> {
+        self.nodes.iter().map(|n| (&n.state, n.cost))
+    }
+}
+
+/// Breadth-first search, every step costs 1 regardless of the costs
+/// reported by the problem. Without a reachable goal the whole connected
+/// component is explored.
+pub fn bfs<P: Problem>(problem: &mut P, start: P::State) -> Explored<P::State> {
+    let mut explored = Explored::new(start);
+    let mut queue = VecDeque::from([0]);
+    let mut out = Vec::new();
+    while let Some(i) = queue.pop_front() {
+        let state = explored.nodes[i].state.clone();
+        if problem.is_goal(&state) {
+            explored.goal = Some(i);
+            break;
+        }
+        problem.visit(
+            &state,
+            &explored,
+            &mut queue.iter().map(|&j| &explored.nodes[j].state),
+        );
+        explored.stats.expanded += 1;
+        let cost = explored.nodes[i].cost + 1;
+        out.clear();
+        problem.neighbors(&state, &mut out);
+        for (next, _) in out.drain(..) {
+            if !explored.contains(&next) {
+                queue.extend(explored.relax(next, cost, i));
+            }
+        }
+        explored.stats.max_frontier = explored.stats.max_frontier.max(queue.len());
+    }
+    explored
+}
+
+/// Cheapest path to a goal, ties are expanded in insertion order. No day
+/// needs it, it is the unguided baseline that [`astar`] is tested against.
+#[cfg(test)]
+pub fn dijkstra<P: Problem>(problem: &mut P, start: P::State) -> Explored<P::State> {
+    best_first(problem, start, false)
+}
+
+/// Cheapest path to a goal, guided by [`Problem::heuristic`], which must
+/// never overestimate.
+pub fn astar<P: Problem>(problem: &mut P, start: P::State) -> Explored<P::State> {
+    best_first(problem, start, true)
+}
+
+fn best_first<P: Problem>(problem: &mut P, start: P::State, guided: bool) -> Explored<P::State> {
+    let estimate = |problem: &P, state: &P::State| {
+        if guided {
+            problem.heuristic(state)
+        } else {
+            0
+        }
+    };
+    let mut explored = Explored::new(start.clone());
+    // (estimated total, cost so far, node), outdated entries are skipped
+    let mut heap = BinaryHeap::from([Reverse((estimate(problem, &start), 0, 0))]);
+    let mut out = Vec::new();
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > explored.nodes[i].cost {
+            continue;
+        }
+        let state = explored.nodes[i].state.clone();
+        if problem.is_goal(&state) {
+            explored.goal = Some(i);
+            break;
+        }
+        problem.visit(
+            &state,
+            &explored,
+            &mut heap
+                .iter()
+                .map(|Reverse((_, _, j))| &explored.nodes[*j].state),
+        );
+        explored.stats.expanded += 1;
+        out.clear();
+        problem.neighbors(&state, &mut out);
+        for (next, step) in out.drain(..) {
+            let h = estimate(problem, &next);
+            if let Some(j) = explored.relax(next, cost + step, i) {
+                heap.push(Reverse((cost + step + h, cost + step, j)));
+            }
+        }
+        explored.stats.max_frontier = explored.stats.max_frontier.max(heap.len());
+    }
+    explored
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Walking on the number line, with steps of 1 costing 1 and jumps of 5
+    /// costing 3.
+    struct Line {
+        target: i64,
+    }
+
+    impl Problem for Line {
+        type State = i64;
+
+        fn neighbors(&mut self, state: &i64, out: &mut Vec<(i64, i64)>) {
+            out.extend([(state - 1, 1), (state + 1, 1), (state + 5, 3)]);
+        }
+
+        fn is_goal(&self, state: &i64) -> bool {
+            *state == self.target
+        }
+
+        fn heuristic(&self, state: &i64) -> i64 {
+            (self.target - state).abs() * 3 / 5
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let mut line = Line { target: 13 };
+        let b = bfs(&mut line, 0);
+        assert_eq!(b.cost(), Some(5));
+        let path = b.path().unwrap();
+        assert_eq!((path.len(), path[0], path[5]), (6, 0, 13));
+
+        let d = dijkstra(&mut line, 0);
+        assert_eq!(d.cost(), Some(9));
+        assert_eq!(d.path().unwrap().len(), 6);
+
+        let a = astar(&mut line, 0);
+        assert_eq!(a.cost(), Some(9));
+        assert!(a.stats.expanded <= d.stats.expanded);
+        assert_eq!(a.cost_to(&5), Some(3));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        struct Ring;
+        impl Problem for Ring {
+            type State = u8;
+            fn neighbors(&mut self, state: &u8, out: &mut Vec<(u8, i64)>) {
+                out.push(((state + 1) % 10, 1));
+            }
+            fn is_goal(&self, _state: &u8) -> bool {
+                false
+            }
+        }
+        let b = bfs(&mut Ring, 0);
+        assert_eq!(b.cost(), None);
+        assert_eq!(b.stats.visited, 10);
+        assert_eq!(b.reached().count(), 10);
+    }
+}
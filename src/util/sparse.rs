@@ -0,0 +1,212 @@
+//! Unbounded two-dimensional grids that only store occupied cells.
+
+use std::cell::Cell;
+use std::fmt::Display;
+
+use super::{hash::FastMap, Grid, Point2};
+
+/// Cells on the infinite plane, stored in a hash map. The bounding box of
+/// the occupied cells is kept up to date as cells are inserted.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: FastMap<Point2, T>,
+    /// Smallest and largest corner of the occupied cells, unless `stale`.
+    bounds: Cell<Option<(Point2, Point2)>>,
+    /// Set when a cell on the edge of the bounding box was removed, the box
+    /// is recomputed on the next query.
+    stale: Cell<bool>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: FastMap::default(),
+            bounds: Cell::new(None),
+            stale: Cell::new(false),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn contains(&self, p: Point2) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    /// Stores `value` at `p`, returns the previous value.
+    pub fn insert(&mut self, p: Point2, value: T) -> Option<T> {
+        if !self.stale.get() {
+            let (min, max) = self.bounds.get().unwrap_or((p, p));
+            self.bounds.set(Some((
+                Point2::xy(min.x().min(p.x()), min.y().min(p.y())),
+                Point2::xy(max.x().max(p.x()), max.y().max(p.y())),
+            )));
+        }
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point2) -> Option<T> {
+        let value = self.cells.remove(&p)?;
+        if let Some((min, max)) = self.bounds.get() {
+            if p.x() == min.x() || p.y() == min.y() || p.x() == max.x() || p.y() == max.y() {
+                self.stale.set(true);
+            }
+        }
+        Some(value)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point2> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Smallest and largest corner of the occupied cells, `None` if there
+    /// are none.
+    pub fn bounds(&self) -> Option<(Point2, Point2)> {
+        if self.stale.replace(false) {
+            let mut ps = self.positions();
+            let bounds = ps.next().map(|p| {
+                ps.fold((p, p), |(min, max), p| {
+                    (
+                        Point2::xy(min.x().min(p.x()), min.y().min(p.y())),
+                        Point2::xy(max.x().max(p.x()), max.y().max(p.y())),
+                    )
+                })
+            });
+            self.bounds.set(bounds);
+        }
+        self.bounds.get()
+    }
+
+    /// Width and height of the bounding box.
+    pub fn dims(&self) -> (i64, i64) {
+        match self.bounds() {
+            Some((min, max)) => (max.x() - min.x() + 1, max.y() - min.y() + 1),
+            None => (0, 0),
+        }
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> SparseGrid<U> {
+        SparseGrid {
+            cells: self.cells.iter().map(|(&p, v)| (p, f(v))).collect(),
+            bounds: self.bounds.clone(),
+            stale: self.stale.clone(),
+        }
+    }
+
+    /// Occupied cells among the eight neighbors of `p`, diagonals included.
+    pub fn count_neighbors8(&self, p: Point2) -> usize {
+        p.surrounding().filter(|q| self.contains(*q)).count()
+    }
+
+    /// The cells of `grid` for which `keep` holds, `(0, 0)` stays the
+    /// origin.
+    pub fn from_grid(grid: &Grid<T>, mut keep: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        grid.iter()
+            .filter(|(_, v)| keep(v))
+            .map(|(p, v)| (p, v.clone()))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(Point2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (p, v) in iter {
+            grid.insert(p, v);
+        }
+        grid
+    }
+}
+
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+/// The bounding box with `.` in unoccupied cells.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+        for y in min.y()..=max.y() {
+            for x in min.x()..=max.x() {
+                match self.get(Point2::xy(x, y)) {
+                    Some(v) => write!(f, "{v}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut g = SparseGrid::new();
+        assert_eq!(g.bounds(), None);
+        g.insert(Point2::xy(2, -1), 'a');
+        g.insert(Point2::xy(-3, 4), 'b');
+        g.insert(Point2::xy(0, 0), 'c');
+        assert_eq!(g.bounds(), Some((Point2::xy(-3, -1), Point2::xy(2, 4))));
+        assert_eq!(g.dims(), (6, 6));
+        g.remove(Point2::xy(-3, 4));
+        assert_eq!(g.bounds(), Some((Point2::xy(0, -1), Point2::xy(2, 0))));
+        g.insert(Point2::xy(1, 1), 'd');
+        assert_eq!(g.to_string(), "..a\nc..\n.d.\n");
+        g.remove(Point2::xy(0, 0));
+        g.remove(Point2::xy(2, -1));
+        g.remove(Point2::xy(1, 1));
+        assert_eq!((g.bounds(), g.dims()), (None, (0, 0)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let g: SparseGrid<()> = [(0, 0), (1, 0), (1, 1), (-1, -1)]
+            .into_iter()
+            .map(|p| (Point2::from(p), ()))
+            .collect();
+        assert_eq!(g.count_neighbors8(Point2::xy(0, 0)), 3);
+        assert_eq!(g.count_neighbors8(Point2::xy(5, 5)), 0);
+    }
+
+    #[test]
+    fn test_grid() {
+        let dense = Grid::parse(b"#..\n.##\n", b'.');
+        let sparse = SparseGrid::from_grid(&dense, |&c| c == b'#');
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.bounds(), Some((Point2::xy(0, 0), Point2::xy(2, 1))));
+        assert_eq!(sparse.map(|_| '#').to_string(), "#..\n.##\n");
+
+        let shifted: SparseGrid<()> = sparse
+            .positions()
+            .map(|p| (p - Point2::xy(5, 5), ()))
+            .collect();
+        assert_eq!(
+            shifted.bounds(),
+            Some((Point2::xy(-5, -5), Point2::xy(-3, -4)))
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Frame based visualization of the grid days.
+//!
+//! A day implements [`Visualize`] by running its simulation and handing
+//! rendered [`Frame`]s to a [`Viz`], which decides which frames are shown and
+//! forwards them to a [`FrameSink`] such as the [`Terminal`].
+
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::util::image::{write_pgm, write_ppm, Gif};
+use crate::util::{take_opt, take_switch, Grid, Point2};
+use crate::{day08, day09, day12, day14, day17, day23, day24};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(64, 160, 43);
+    pub const YELLOW: Rgb = Rgb(223, 175, 0);
+    pub const CYAN: Rgb = Rgb(42, 161, 152);
+    pub const BROWN: Rgb = Rgb(160, 100, 50);
+
+    /// Linear interpolation between `self` (t = 0) and `other` (t = 1).
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let f = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t.clamp(0.0, 1.0)) as u8;
+        Rgb(f(self.0, other.0), f(self.1, other.1), f(self.2, other.2))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Rgb,
+    pub bg: Option<Rgb>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: Rgb::WHITE,
+            bg: None,
+        }
+    }
+}
+
+/// A rendered grid of colored characters.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub caption: String,
+    cells: Grid<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            caption: String::new(),
+            cells: Grid::new(width, height, Cell::default()),
+        }
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Sets a cell, positions outside of the frame are ignored.
+    pub fn set(&mut self, x: i64, y: i64, ch: char, fg: Rgb) {
+        if let Some(cell) = self.cells.get_mut(Point2::xy(x, y)) {
+            cell.ch = ch;
+            cell.fg = fg;
+        }
+    }
+
+    /// Colors the background of a cell, positions outside of the frame are
+    /// ignored.
+    pub fn highlight(&mut self, x: i64, y: i64, bg: Rgb) {
+        if let Some(cell) = self.cells.get_mut(Point2::xy(x, y)) {
+            cell.bg = Some(bg);
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.rows()
+    }
+
+    /// Rasterizes the frame with `scale` by `scale` pixels per cell, three
+    /// bytes per pixel. Cells are filled with their background, or their
+    /// foreground if they have none; highlighted characters show as a dot in
+    /// the middle of the cell.
+    pub fn rgb(&self, scale: usize) -> Vec<u8> {
+        let (w, h) = (self.width() * scale, self.height() * scale);
+        let mut pixels = vec![0; w * h * 3];
+        let dot = scale / 3..scale - scale / 3;
+        for (y, row) in self.rows().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let fill = match (cell.bg, cell.ch) {
+                    (Some(bg), _) => bg,
+                    (None, ' ') => Rgb::BLACK,
+                    (None, _) => cell.fg,
+                };
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let marked = cell.bg.is_some()
+                            && cell.ch != ' '
+                            && scale >= 3
+                            && dot.contains(&dx)
+                            && dot.contains(&dy);
+                        let Rgb(r, g, b) = if marked { cell.fg } else { fill };
+                        let i = ((y * scale + dy) * w + x * scale + dx) * 3;
+                        pixels[i..i + 3].copy_from_slice(&[r, g, b]);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.caption.is_empty() {
+            writeln!(f, "{}", self.caption)?;
+        }
+        for row in self.rows() {
+            for cell in row {
+                let Rgb(r, g, b) = cell.fg;
+                write!(f, "\x1b[38;2;{r};{g};{b}m")?;
+                if let Some(Rgb(r, g, b)) = cell.bg {
+                    write!(f, "\x1b[48;2;{r};{g};{b}m")?;
+                }
+                write!(f, "{}\x1b[0m", cell.ch)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Destination of the frames selected by a [`Viz`].
+pub trait FrameSink {
+    fn show(&mut self, frame: &Frame) -> Result<()>;
+
+    /// Called once after the last frame.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Draws frames in place on the terminal.
+pub struct Terminal {
+    /// Pause after each frame.
+    pub delay: Duration,
+    /// Wait for enter after each frame instead of sleeping.
+    pub step: bool,
+}
+
+impl FrameSink for Terminal {
+    fn show(&mut self, frame: &Frame) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        write!(stdout, "\x1b[H\x1b[2J{}", frame)?;
+        stdout.flush()?;
+        if self.step {
+            std::io::stdin().lock().read_line(&mut String::new())?;
+        } else {
+            sleep(self.delay);
+        }
+        Ok(())
+    }
+}
+
+/// Writes each frame to a numbered PPM or, with `gray`, PGM file.
+pub struct ImageDir {
+    pub dir: PathBuf,
+    pub gray: bool,
+    pub scale: usize,
+    pub count: usize,
+}
+
+impl FrameSink for ImageDir {
+    fn show(&mut self, frame: &Frame) -> Result<()> {
+        let (w, h) = (frame.width() * self.scale, frame.height() * self.scale);
+        let rgb = frame.rgb(self.scale);
+        let ext = if self.gray { "pgm" } else { "ppm" };
+        let path = self.dir.join(format!("frame{:05}.{ext}", self.count));
+        let mut f = BufWriter::new(File::create(path)?);
+        if self.gray {
+            let luma: Vec<u8> = rgb
+                .chunks(3)
+                .map(|p| (0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64) as u8)
+                .collect();
+            write_pgm(&mut f, w, h, &luma)?;
+        } else {
+            write_ppm(&mut f, w, h, &rgb)?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Collects the frames into an animated GIF that is written at the end.
+pub struct GifFile {
+    pub path: PathBuf,
+    pub scale: usize,
+    /// Hundredths of a second per frame.
+    pub delay: u16,
+    pub gif: Gif,
+}
+
+impl FrameSink for GifFile {
+    fn show(&mut self, frame: &Frame) -> Result<()> {
+        let (w, h) = (frame.width() * self.scale, frame.height() * self.scale);
+        self.gif.add_frame(w, h, &frame.rgb(self.scale), self.delay);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let mut f = BufWriter::new(File::create(&self.path)?);
+        self.gif.write(&mut f)?;
+        f.flush()?;
+        Ok(())
+    }
+}
+
+/// Frame selection shared by all visualizations.
+pub struct Viz {
+    /// Show every n-th frame.
+    pub every: usize,
+    /// Skip the first frames.
+    pub start: usize,
+    ticks: usize,
+    sink: Option<Box<dyn FrameSink>>,
+    error: Option<Box<dyn Error>>,
+}
+
+impl Viz {
+    pub fn new(sink: Box<dyn FrameSink>) -> Self {
+        Self {
+            every: 1,
+            start: 0,
+            ticks: 0,
+            sink: Some(sink),
+            error: None,
+        }
+    }
+
+    /// A `Viz` that never shows anything, used by the regular solutions.
+    pub fn off() -> Self {
+        Self {
+            every: 1,
+            start: 0,
+            ticks: 0,
+            sink: None,
+            error: None,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Advances to the next frame and returns whether it will be shown.
+    pub fn tick(&mut self) -> bool {
+        if self.sink.is_none() || self.error.is_some() {
+            return false;
+        }
+        let t = self.ticks;
+        self.ticks += 1;
+        t >= self.start && (t - self.start).is_multiple_of(self.every.max(1))
+    }
+
+    /// Shows a frame regardless of the frame selection, e.g. the final state.
+    /// `render` is not called if the `Viz` is off.
+    pub fn show(&mut self, render: impl FnOnce() -> Frame) {
+        if let (Some(sink), None) = (&mut self.sink, &self.error) {
+            if let Err(e) = sink.show(&render()) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Advances to the next frame and only renders it if it is shown.
+    pub fn frame(&mut self, render: impl FnOnce() -> Frame) {
+        if self.tick() {
+            self.show(render);
+        }
+    }
+
+    /// Finishes the sink and returns its first error.
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        match &mut self.sink {
+            Some(sink) => sink.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A simulation or search that can be watched frame by frame.
+pub trait Visualize {
+    /// Runs the simulation, passing its frames to `viz`.
+    fn visualize(&self, viz: &mut Viz) -> Result<()>;
+}
+
+/// Visualizations by day and part.
+pub fn visualization(day_no: usize, part_no: usize) -> Result<Box<dyn Visualize>> {
+    Ok(match day_no {
+        8 => Box::new(day08::Trees::new(part_no)?),
+        9 => Box::new(day09::Rope::new(part_no)?),
+        12 => Box::new(day12::Hike::new(part_no)?),
+        14 => Box::new(day14::Sand::new(part_no)?),
+        17 => Box::new(day17::Tower::new()?),
+        23 => Box::new(day23::Elves::new(part_no)?),
+        24 => Box::new(day24::Expedition::new(part_no)?),
+        _ => return Err(format!("no visualization for day {day_no}").into()),
+    })
+}
+
+/// `viz <day> [part] [--fps N] [--every N] [--start N] [--step]` animates in
+/// the terminal, `--ppm DIR`, `--pgm DIR` or `--gif FILE` with `--scale N`
+/// export the frames instead.
+pub fn run(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let fps: f64 = take_opt(&mut args, "--fps")?.map_or(Ok(20.0), |x| x.parse())?;
+    let every = take_opt(&mut args, "--every")?.map_or(Ok(1), |x| x.parse())?;
+    let start = take_opt(&mut args, "--start")?.map_or(Ok(0), |x| x.parse())?;
+    let step = take_switch(&mut args, "--step");
+    let scale = take_opt(&mut args, "--scale")?.map_or(Ok(4), |x| x.parse())?;
+    let ppm = take_opt(&mut args, "--ppm")?;
+    let pgm = take_opt(&mut args, "--pgm")?;
+    let gif = take_opt(&mut args, "--gif")?;
+    let day_no: usize = args.first().ok_or("missing day")?.parse()?;
+    let part_no: usize = args.get(1).map_or("1", |x| x.as_str()).parse()?;
+
+    let image_dir = |dir: String, gray| -> Result<Box<dyn FrameSink>> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Box::new(ImageDir {
+            dir: dir.into(),
+            gray,
+            scale,
+            count: 0,
+        }))
+    };
+    let sink: Box<dyn FrameSink> = match (ppm, pgm, gif) {
+        (None, None, None) => Box::new(Terminal {
+            delay: Duration::from_secs_f64(1.0 / fps),
+            step,
+        }),
+        (Some(dir), None, None) => image_dir(dir, false)?,
+        (None, Some(dir), None) => image_dir(dir, true)?,
+        (None, None, Some(path)) => Box::new(GifFile {
+            path: path.into(),
+            scale,
+            delay: (100.0 / fps).round().max(2.0) as u16,
+            gif: Gif::new(),
+        }),
+        _ => return Err("expected at most one of --ppm, --pgm and --gif".into()),
+    };
+    let mut viz = Viz::new(sink);
+    viz.every = every;
+    viz.start = start;
+    visualization(day_no, part_no)?.visualize(&mut viz)
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+
+use crate::corpus::{run_variant, Outcome};
+use crate::error::Result;
+use crate::util::{format_duration, take_opt};
+
+/// Changes are collected until nothing happened for this long, so that a
+/// save touching several files only triggers a single run.
+const QUIET: Duration = Duration::from_millis(100);
+
+/// Returns the variant names of every part of a day as printed by
+/// `list --variants` of `exe`, so that variants added since the watcher
+/// started are picked up after a rebuild.
+fn variants(exe: &Path, day_no: usize) -> Result<Vec<Vec<String>>> {
+    let output = Command::new(exe)
+        .args(["list", "--variants", &day_no.to_string()])
+        .output()?;
+    if !output.status.success() {
+        return Err(format!("no solution for day {day_no} in {}", exe.display()).into());
+    }
+    parse_variants(&String::from_utf8(output.stdout)?)
+}
+
+/// Groups `<part> <variant>` lines by part.
+fn parse_variants(list: &str) -> Result<Vec<Vec<String>>> {
+    let mut parts: Vec<Vec<String>> = Vec::new();
+    for line in list.lines() {
+        let (part_no, name) = line
+            .split_once(' ')
+            .ok_or(format!("bad variant line {line:?}"))?;
+        let part_no: usize = part_no.parse()?;
+        if part_no == 0 || part_no > parts.len() + 1 {
+            return Err(format!("bad variant line {line:?}").into());
+        }
+        if part_no > parts.len() {
+            parts.push(Vec::new());
+        }
+        parts[part_no - 1].push(name.to_string());
+    }
+    Ok(parts)
+}
+
+/// Runs every selected variant and prints its answer and time next to the
+/// previous run.
+fn rerun(
+    exe: &Path,
+    day_no: usize,
+    part: Option<usize>,
+    timeout: Duration,
+    previous: &mut BTreeMap<(usize, String), Outcome>,
+) -> Result<()> {
+    for (part_no, names) in (1..).zip(variants(exe, day_no)?) {
+        if part.is_some_and(|p| p != part_no) {
+            continue;
+        }
+        for name in names {
+            let outcome = run_variant(exe, day_no, part_no, &name, None, timeout)?;
+            let line = match (&outcome, previous.get(&(part_no, name.clone()))) {
+                (Outcome::Answer(a, t), Some(Outcome::Answer(prev_a, prev_t))) => {
+                    let answer = if a == prev_a {
+                        a.clone()
+                    } else {
+                        format!("\x1b[93m{a}\x1b[0m (was {prev_a})")
+                    };
+                    let time = match (t, prev_t) {
+                        (Some(t), Some(prev_t)) => {
+                            let change = t.as_secs_f64() / prev_t.as_secs_f64() - 1.0;
+                            let color = if change < -0.05 {
+                                32
+                            } else if change > 0.05 {
+                                91
+                            } else {
+                                0
+                            };
+                            format!(
+                                "{} (\x1b[{color}m{:+.0}%\x1b[0m)",
+                                format_duration(*t).trim(),
+                                change * 100.0
+                            )
+                        }
+                        (Some(t), None) => format_duration(*t).trim().to_string(),
+                        _ => String::new(),
+                    };
+                    format!("{answer}  {time}")
+                }
+                (Outcome::Answer(a, t), _) => format!(
+                    "{a}  {}",
+                    t.map_or(String::new(), |t| format_duration(t).trim().to_string())
+                ),
+                (Outcome::Panicked(at, msg), _) => {
+                    format!("\x1b[91mpanicked\x1b[0m at {at}: {msg}")
+                }
+                (Outcome::Failed, _) => "\x1b[91mfailed\x1b[0m".to_string(),
+                (Outcome::Timeout, _) => "\x1b[93mtimeout\x1b[0m".to_string(),
+            };
+            println!("  {name}: {line}");
+            previous.insert((part_no, name), outcome);
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the binary with the same profile as the running one.
+fn rebuild() -> Result<bool> {
+    let mut cargo = Command::new("cargo");
+    cargo.arg("build");
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    Ok(cargo.status()?.success())
+}
+
+/// `watch <day> [part] [--timeout S]` re-runs the registered variants of a
+/// day whenever one of its inputs changes, and rebuilds first if a source
+/// file changed.
+pub fn run(args: impl Iterator<Item = String>) -> Result<()> {
+    let mut args: Vec<String> = args.collect();
+    let timeout = take_opt(&mut args, "--timeout")?
+        .map(|x| x.parse::<f64>().map(Duration::from_secs_f64))
+        .transpose()?
+        .unwrap_or(Duration::from_secs(10));
+    let day_no: usize = args.first().ok_or("missing day")?.parse()?;
+    let part: Option<usize> = args.get(1).map(|x| x.parse()).transpose()?;
+    // The path stays valid when cargo replaces the binary, the running
+    // watcher keeps its old code and only the children are rebuilt.
+    let exe = std::env::current_exe()?;
+
+    let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+    let flags = AddWatchFlags::IN_CLOSE_WRITE | AddWatchFlags::IN_MOVED_TO;
+    let inputs = inotify.add_watch("inputs", flags)?;
+    let sources: Vec<WatchDescriptor> = ["src", "src/util"]
+        .iter()
+        .map(|dir| inotify.add_watch(*dir, flags))
+        .collect::<std::result::Result<_, _>>()?;
+    let prefix = format!("day{:02}", day_no);
+
+    let mut previous = BTreeMap::new();
+    println!("Day {day_no}");
+    rerun(&exe, day_no, part, timeout, &mut previous)?;
+
+    let mut changed_input = None;
+    let mut changed_source = None;
+    let mut last_event = Instant::now();
+    loop {
+        match inotify.read_events() {
+            Ok(events) => {
+                for event in events {
+                    let name = event.name.unwrap_or_default();
+                    let name = PathBuf::from(&name);
+                    if event.wd == inputs && name.to_string_lossy().starts_with(&prefix) {
+                        changed_input = Some(name);
+                    } else if sources.contains(&event.wd)
+                        && name.extension() == Some(OsStr::new("rs"))
+                    {
+                        changed_source = Some(name);
+                    }
+                }
+                last_event = Instant::now();
+            }
+            Err(Errno::EAGAIN) => {
+                if (changed_input.is_some() || changed_source.is_some())
+                    && last_event.elapsed() >= QUIET
+                {
+                    if let Some(name) = changed_source.take() {
+                        println!("\n{} changed, rebuilding", name.display());
+                        if !rebuild()? {
+                            println!("\x1b[91mbuild failed\x1b[0m");
+                            changed_input = None;
+                            continue;
+                        }
+                    }
+                    if let Some(name) = changed_input.take() {
+                        println!("\ninputs/{} changed", name.display());
+                    }
+                    rerun(&exe, day_no, part, timeout, &mut previous)?;
+                }
+                sleep(Duration::from_millis(20));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::fmt::Debug;
+
+    use super::*;
+    use crate::meta::variant_lines;
+    use crate::Solution;
+
+    #[test]
+    fn test_variants() -> Result<()> {
+        let f = || Box::new(|| Box::new(0) as Box<dyn Debug>) as Solution;
+        let solutions = vec![vec![
+            BTreeMap::from([("day01::solve_a", f())]),
+            BTreeMap::from([("day01::solve_b", f()), ("day01::solve_b_opt", f())]),
+        ]];
+        let lines = variant_lines(&solutions, 1)?;
+        assert_eq!(lines, ["1 solve_a", "2 solve_b", "2 solve_b_opt"]);
+        assert_eq!(
+            parse_variants(&lines.join("\n"))?,
+            [vec!["solve_a"], vec!["solve_b", "solve_b_opt"]]
+        );
+        assert!(variant_lines(&solutions, 2).is_err());
+        assert!(parse_variants("2 solve_b").is_err());
+        Ok(())
+    }
+}